@@ -2,22 +2,89 @@ use std::{
     collections::{BTreeSet, VecDeque},
     error::Error,
     fs::{read_to_string, File},
-    io::Write,
+    io::{self, Write},
     path::Path,
 };
 
-use crate::{trails::TrailPoint, PI, WINDOW_SIZE};
+use crate::{
+    boundary::{Absorbed, Boundary, BoundaryKind, MAX_ABSORBED},
+    constraints::{Constraint, Link, ROD_ITERATIONS},
+    events::{Callback, Removal, SimEvent, Subscribers},
+    forces::{pulls, ForceModel, ForceSpec, Gravity},
+    impacts::{
+        debris_masses, disruption_energy, ejection_speeds, largest_remnant_fraction,
+        specific_impact_energy, FRAGMENT_THRESHOLD, MIN_DEBRIS_RADIUS,
+    },
+    orbits::{cross, fates, Fate},
+    particles::{draw_particles, ParticleSerializable, TestParticle},
+    tides::{roche_limit, MAX_FRAGMENTS, MIN_FRAGMENTS, MIN_TIDAL_MASS_RATIO},
+    trails::{TrailPoint, DEATH_AGE},
+    trajectory::TrajectoryLog,
+    triggers::{Action, Condition, Trigger},
+    PI, WINDOW_SIZE,
+};
 use serde::{Deserialize, Serialize};
 use sfml::{
-    graphics::{CircleShape, Color, Drawable, RenderStates, RenderTarget, Shape, Transformable},
+    graphics::{
+        CircleShape, Color, Drawable, PrimitiveType, RenderStates, RenderTarget, Shape,
+        Transformable, Vertex,
+    },
     system::Vector2f,
 };
 const ERROR_MARGIN: f32 = 0.01;
+//bodies are solid balls, which have a moment of inertia of 2/5 m r^2
+const INERTIA_FACTOR: f32 = 0.4;
+//bodies smaller than this many pixels across are too small to show which way they face
+const MIN_MARKER_RADIUS: f32 = 3.0;
+//how the pull between two bodies falls off with the distance between them
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForceLaw {
+    //force goes as 1/r, which is what gravity would be in a flat 2d world.
+    //worlds were always simulated with this before there was a choice
+    #[default]
+    InverseLinear,
+    //force goes as 1/r^2, like real gravity
+    InverseSquare,
+}
+impl ForceLaw {
+    pub const ALL: [ForceLaw; 2] = [ForceLaw::InverseLinear, ForceLaw::InverseSquare];
+    //the n in force = G * m / r^n
+    pub fn exponent(&self) -> f32 {
+        match self {
+            ForceLaw::InverseLinear => 1.0,
+            ForceLaw::InverseSquare => 2.0,
+        }
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            ForceLaw::InverseLinear => "inverse_linear",
+            ForceLaw::InverseSquare => "inverse_square",
+        }
+    }
+    pub fn from_name(name: &str) -> Option<ForceLaw> {
+        ForceLaw::ALL.iter().copied().find(|law| law.name() == name)
+    }
+    //speed of a circular orbit `distance` away from `mass`, from v^2 / r = G * M / r^n
+    pub fn circular_speed(&self, gravity: f32, mass: f32, distance: f32) -> f32 {
+        (gravity * mass * distance.powf(1.0 - self.exponent())).sqrt()
+    }
+    //energy per unit mass of something `distance` away from `mass`, only differences matter
+    pub fn potential(&self, gravity: f32, mass: f32, distance: f32) -> f32 {
+        match self {
+            ForceLaw::InverseLinear => gravity * mass * distance.ln(),
+            ForceLaw::InverseSquare => -gravity * mass / distance,
+        }
+    }
+}
 type Time = f32;
 #[derive(Debug)]
 pub struct SpaceBody<'a> {
     pub x: f32,
     pub y: f32,
+    //position at the start of the last physics tick, used to interpolate when drawing
+    prev_x: f32,
+    prev_y: f32,
     xv: f32,
     yv: f32,
     ax: f32,
@@ -28,6 +95,18 @@ pub struct SpaceBody<'a> {
     pub shape: CircleShape<'a>,
     immovable: bool,
     index: usize,
+    //a piece of a body that was pulled apart, which isn't pulled apart again
+    rubble: bool,
+    //stays the same for as long as the body exists, unlike its index, so springs can find it
+    id: u64,
+    charge: f32,
+    //which way the body faces and how fast that turns, anticlockwise on screen being negative
+    //like the y axis
+    angle: f32,
+    prev_angle: f32,
+    spin: f32,
+    //what it was found to be doing after the last step, none until it has been looked at
+    fate: Option<Fate>,
 }
 impl Eq for SpaceBody<'_> {}
 impl PartialEq for SpaceBody<'_> {
@@ -41,14 +120,22 @@ impl PartialEq for SpaceBody<'_> {
     }
 }
 impl SpaceBody<'_> {
-    pub fn update_shape_position(&mut self, cam_pos: &Vector2f) {
+    pub fn update_shape_position(
+        &mut self,
+        cam_pos: &Vector2f,
+        zoom: f32,
+        alpha: f32,
+        screen_center: Vector2f,
+    ) {
+        let radius = self.radius * zoom;
+        let position = self.interpolated_pos(alpha);
         self.shape.set_position(Vector2f::new(
-            self.x - self.radius - cam_pos.x + WINDOW_SIZE.0 / 2.0,
-            self.y - self.radius - cam_pos.y + WINDOW_SIZE.1 / 2.0,
+            (position.x - cam_pos.x) * zoom - radius + screen_center.x,
+            (position.y - cam_pos.y) * zoom - radius + screen_center.y,
         ));
         let error_margin = 0.1;
-        if (self.radius - self.shape.radius()).abs() > error_margin {
-            self.shape.set_radius(self.radius);
+        if (radius - self.shape.radius()).abs() > error_margin {
+            self.shape.set_radius(radius);
         }
     }
     #[allow(clippy::clippy::too_many_arguments)]
@@ -65,6 +152,8 @@ impl SpaceBody<'_> {
         SpaceBody {
             x: position.0,
             y: position.1,
+            prev_x: position.0,
+            prev_y: position.1,
             xv,
             yv,
             ax: 0.0,
@@ -79,11 +168,61 @@ impl SpaceBody<'_> {
             },
             immovable,
             index,
+            rubble: false,
+            id: 0,
+            charge: 0.0,
+            angle: 0.0,
+            prev_angle: 0.0,
+            spin: 0.0,
+            fate: None,
         }
     }
     pub fn pos2f(&self) -> Vector2f {
         Vector2f::new(self.x, self.y)
     }
+    pub fn velocity(&self) -> Vector2f {
+        Vector2f::new(self.xv, self.yv)
+    }
+    pub fn mass(&self) -> f32 {
+        self.mass
+    }
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+    pub fn charge(&self) -> f32 {
+        self.charge
+    }
+    pub fn set_charge(&mut self, charge: f32) {
+        self.charge = charge;
+    }
+    pub fn spin(&self) -> f32 {
+        self.spin
+    }
+    pub fn fate(&self) -> Option<Fate> {
+        self.fate
+    }
+    pub fn set_spin(&mut self, spin: f32) {
+        self.spin = spin;
+    }
+    pub fn moment_of_inertia(&self) -> f32 {
+        INERTIA_FACTOR * self.mass * self.radius * self.radius
+    }
+    pub fn spin_angular_momentum(&self) -> f32 {
+        self.moment_of_inertia() * self.spin
+    }
+    //which way the body faced `alpha` of the way through the last physics tick
+    pub fn interpolated_angle(&self, alpha: f32) -> f32 {
+        //the shorter way round, in case the angle wrapped during the tick
+        let turned = (self.angle - self.prev_angle + PI).rem_euclid(2.0 * PI) - PI;
+        self.prev_angle + turned * alpha
+    }
+    //where the body was `alpha` of the way through the last physics tick
+    pub fn interpolated_pos(&self, alpha: f32) -> Vector2f {
+        Vector2f::new(
+            self.prev_x + (self.x - self.prev_x) * alpha,
+            self.prev_y + (self.y - self.prev_y) * alpha,
+        )
+    }
 }
 #[derive(Debug)]
 pub struct WorldSpace<'a> {
@@ -95,10 +234,68 @@ pub struct WorldSpace<'a> {
     stopped: bool,
     pub cam_pos: Vector2f,
     pub focused_idx: Option<usize>,
+    trail_length: usize,
+    zoom: f32,
+    substeps: u32,
+    sim_time: f64,
+    tick_rate: f32,
+    trails_enabled: bool,
+    force_law: ForceLaw,
+    view_size: Vector2f,
+    particles: Vec<TestParticle>,
+    //whether particles that hit a body disappear, or carry on through it
+    absorb_particles: bool,
+    //whether bodies that get too close to a much heavier one are pulled apart
+    tidal_disruption: bool,
+    fragment_count: u32,
+    show_hill_sphere: bool,
+    //whether hard enough impacts shatter bodies instead of merging them
+    fragmentation: bool,
+    //everything that accelerates the bodies, added up each step
+    forces: Vec<ForceSpec>,
+    constraints: Vec<Constraint>,
+    boundary: Boundary,
+    //bodies that went out through an absorbing boundary, newest last
+    absorbed: VecDeque<Absorbed>,
+    //how far from everything else a body has to be before it can count as escaping
+    escape_radius: f32,
+    //what happened since they were last taken, the oldest going first if nobody takes them
+    events: VecDeque<SimEvent>,
+    subscribers: Subscribers<'a>,
+    //how many events there have been, taken or not
+    events_emitted: u64,
+    triggers: Vec<Trigger>,
+    //a stop trigger went off, so the run is over
+    finished: bool,
+    trajectory: Option<TrajectoryLog>,
+    //the id the next body added gets
+    next_id: u64,
 }
+//totals over every body in the world
+#[derive(Debug, Clone, Copy)]
+pub struct Stats {
+    pub bodies: usize,
+    pub particles: usize,
+    pub mass: f32,
+    pub center_of_mass: Vector2f,
+    pub momentum: Vector2f,
+    pub kinetic_energy: f32,
+    //orbital and spin together
+    pub angular_momentum: f32,
+}
+pub const MAX_SUBSTEPS: u32 = 64;
+pub const DEFAULT_TICK_RATE: f32 = 45.0;
+pub const MIN_TICK_RATE: f32 = 1.0;
+pub const MAX_TICK_RATE: f32 = 1000.0;
+pub const SAVE_PATH: &str = "space.json";
+pub const DEFAULT_FRAGMENTS: u32 = 4;
+pub const DEFAULT_ESCAPE_RADIUS: f32 = 3000.0;
+const MAX_EVENTS: usize = 1000;
+//fragments are put this many of their own radii apart so they don't merge straight back
+const FRAGMENT_SPACING: f32 = 2.2;
 
-impl From<SpaceBody<'_>> for BodySerializable {
-    fn from(other: SpaceBody<'_>) -> Self {
+impl From<&SpaceBody<'_>> for BodySerializable {
+    fn from(other: &SpaceBody<'_>) -> Self {
         let other_color = other.shape.fill_color();
         BodySerializable {
             x: other.x,
@@ -111,7 +308,13 @@ impl From<SpaceBody<'_>> for BodySerializable {
             radius: other.radius,
             immovable: other.immovable,
             color_rgb: (other_color.r, other_color.g, other_color.b),
+            alpha: other_color.a,
             index: other.index,
+            rubble: other.rubble,
+            id: Some(other.id),
+            charge: other.charge,
+            angle: other.angle,
+            spin: other.spin,
         }
     }
 }
@@ -120,6 +323,8 @@ impl From<BodySerializable> for SpaceBody<'_> {
         SpaceBody {
             x: other.x,
             y: other.y,
+            prev_x: other.x,
+            prev_y: other.y,
             xv: other.xv,
             yv: other.yv,
             ax: other.ax,
@@ -130,14 +335,23 @@ impl From<BodySerializable> for SpaceBody<'_> {
             next_trail: 10,
             shape: {
                 let mut c = CircleShape::new(other.radius, (other.radius * PI) as u32);
-                c.set_fill_color(Color::rgb(
+                c.set_fill_color(Color::rgba(
                     other.color_rgb.0,
                     other.color_rgb.1,
                     other.color_rgb.2,
+                    other.alpha,
                 ));
                 c
             },
             index: other.index,
+            rubble: other.rubble,
+            //bodies saved before there were ids get one when the world is loaded
+            id: other.id.unwrap_or(0),
+            charge: other.charge,
+            angle: other.angle,
+            prev_angle: other.angle,
+            spin: other.spin,
+            fate: None,
         }
     }
 }
@@ -156,11 +370,12 @@ impl<'a> WorldSpace<'a> {
                 planet.x += planet.xv * self.dt;
                 planet.y += planet.yv * self.dt;
             }
+            planet.angle = (planet.angle + planet.spin * self.dt) % (2.0 * PI);
         }
     }
-    fn update_planets_shape_pos(&mut self) {
+    fn update_planets_shape_pos(&mut self, alpha: f32) {
         for planet in &mut self.bodies {
-            planet.update_shape_position(&self.cam_pos);
+            planet.update_shape_position(&self.cam_pos, self.zoom, alpha, self.view_size / 2.0);
         }
     }
     fn update_time(&mut self) {
@@ -169,11 +384,11 @@ impl<'a> WorldSpace<'a> {
             planet.yv += planet.ay * self.dt;
         }
     }
-    fn update_cam_pos(&mut self) {
+    fn update_cam_pos(&mut self, alpha: f32) {
         if let Some(idx) = self.focused_idx {
             if let Some(real) = self.get_nearest_index(idx) {
                 let body = &self.bodies[real];
-                self.cam_pos = body.pos2f();
+                self.cam_pos = body.interpolated_pos(alpha);
             }
         }
     }
@@ -187,14 +402,14 @@ impl<'a> WorldSpace<'a> {
             self.get_nearest_index(index - 1)
         }
     }
-    fn collide(&self, idx_a: usize, idx_b: usize) -> SpaceBody<'a> {
+    fn merge(&self, idx_a: usize, idx_b: usize) -> SpaceBody<'a> {
         assert!(self.bodies.get(idx_a).is_some());
         assert!(self.bodies.get(idx_b).is_some());
         assert_ne!(idx_a, idx_b);
         let body_a = self.bodies.get(idx_a).unwrap();
         let body_b = &self.bodies[idx_b];
         let total_mass = body_a.mass + body_b.mass;
-        let (r, g, b) = (
+        let (r, g, b, a) = (
             ((body_a.shape.fill_color().r as f32 * body_a.mass
                 + body_b.shape.fill_color().r as f32 * body_b.mass)
                 / total_mass) as u8,
@@ -204,29 +419,134 @@ impl<'a> WorldSpace<'a> {
             ((body_a.shape.fill_color().b as f32 * body_a.mass
                 + body_b.shape.fill_color().b as f32 * body_b.mass)
                 / total_mass) as u8,
+            ((body_a.shape.fill_color().a as f32 * body_a.mass
+                + body_b.shape.fill_color().a as f32 * body_b.mass)
+                / total_mass) as u8,
         );
         let radius = (body_a.radius * body_a.radius * body_a.radius
             + body_b.radius * body_b.radius * body_b.radius)
             .cbrt();
         let xv = (body_a.xv * body_a.mass + body_b.xv * body_b.mass) / total_mass;
         let yv = (body_a.yv * body_a.mass + body_b.yv * body_b.mass) / total_mass;
-        let _mass_diff = body_a.mass - body_b.mass;
-        let x_diff = body_a.x - body_b.x;
-        let y_diff = body_a.y - body_b.x;
-        let position = (
-            body_a.x / 2.0 + body_b.x / 2.0 + x_diff / total_mass,
-            body_a.y / 2.0 + body_b.y / 2.0 + y_diff / total_mass,
-        );
-        SpaceBody::new(
-            position,
+        //the copy of b nearest a, which is just b unless the world wraps around
+        let separation = self.boundary.separation(body_a.pos2f(), body_b.pos2f());
+        let b_pos = body_a.pos2f() + separation;
+        //at the centre of mass, so merging doesn't move it or change the angular momentum
+        let position = body_a.pos2f() + separation * (body_b.mass / total_mass);
+        let mut merged = SpaceBody::new(
+            (position.x, position.y),
             total_mass,
             radius,
             xv,
             yv,
             false,
-            Color::rgb(r, g, b),
+            Color::rgba(r, g, b, a),
             self.bodies.len(),
-        )
+        );
+        merged.charge = body_a.charge + body_b.charge;
+        //how the two went round each other becomes spin, along with the spin they had
+        let orbital = body_a.mass * body_b.mass / total_mass
+            * cross(
+                body_a.pos2f() - b_pos,
+                body_a.velocity() - body_b.velocity(),
+            );
+        let angular_momentum =
+            body_a.spin_angular_momentum() + body_b.spin_angular_momentum() + orbital;
+        merged.spin = angular_momentum / merged.moment_of_inertia();
+        merged
+    }
+    //what is left after bodies `idx_a` and `idx_b` hit, which is one merged body unless
+    //fragmentation is on and the impact was hard enough to shatter them. then it is the
+    //largest remnant first, followed by debris thrown out evenly around it
+    fn collide(&self, idx_a: usize, idx_b: usize) -> Vec<SpaceBody<'a>> {
+        let merged = self.merge(idx_a, idx_b);
+        let body_a = &self.bodies[idx_a];
+        let body_b = &self.bodies[idx_b];
+        if !self.fragmentation || body_a.immovable || body_b.immovable {
+            return vec![merged];
+        }
+        let relative = Vector2f::new(body_a.xv - body_b.xv, body_a.yv - body_b.yv);
+        let speed = (relative.x * relative.x + relative.y * relative.y).sqrt();
+        let total = merged.mass;
+        let energy = specific_impact_energy(body_a.mass, body_b.mass, speed);
+        let disruption = disruption_energy(self.force_law, self.gravity, total, merged.radius);
+        if energy < FRAGMENT_THRESHOLD * disruption {
+            return vec![merged];
+        }
+        let remnant = total * largest_remnant_fraction(energy, disruption);
+        let min_mass = total * (MIN_DEBRIS_RADIUS / merged.radius).powi(3);
+        let masses = debris_masses(total - remnant, min_mass);
+        if masses.is_empty() {
+            return vec![merged];
+        }
+        //whatever the debris doesn't take, so no mass is lost to rounding
+        let remnant = total - masses.iter().sum::<f32>();
+        //every piece keeps the density of the merged body
+        let radius_of = |mass: f32| merged.radius * (mass / total).cbrt();
+        let remnant_radius = radius_of(remnant);
+        let escape_speed = 2f32.sqrt()
+            * self
+                .force_law
+                .circular_speed(self.gravity, remnant, remnant_radius);
+        let speeds = ejection_speeds(&masses, escape_speed, energy * total);
+        //far enough out that no two pieces touch, or they would merge straight back
+        let biggest = radius_of(masses[0]);
+        let gap = PI / masses.len() as f32;
+        let ring = ((remnant_radius + biggest) * 1.1).max(if masses.len() > 1 {
+            1.1 * biggest / gap.sin()
+        } else {
+            0.0
+        });
+        let center = body_a.pos2f()
+            + self.boundary.separation(body_a.pos2f(), body_b.pos2f()) * (body_b.mass / total);
+        let velocity = Vector2f::new(merged.xv, merged.yv);
+        let color = merged.shape.fill_color();
+        let first_angle = relative.y.atan2(relative.x);
+        let mut debris = Vec::with_capacity(masses.len());
+        let mut kick_sum = Vector2f::new(0.0, 0.0);
+        for (k, (mass, speed)) in masses.iter().zip(speeds.iter()).enumerate() {
+            let (sin, cos) = (first_angle + 2.0 * gap * k as f32).sin_cos();
+            let direction = Vector2f::new(cos, sin);
+            let position = center + direction * ring;
+            let kick = direction * *speed;
+            kick_sum += kick * *mass;
+            let mut piece = SpaceBody::new(
+                (position.x, position.y),
+                *mass,
+                radius_of(*mass),
+                velocity.x + kick.x,
+                velocity.y + kick.y,
+                false,
+                color,
+                0,
+            );
+            piece.rubble = true;
+            //charge is shared out like the mass
+            piece.charge = merged.charge * mass / total;
+            debris.push(piece);
+        }
+        //the remnant recoils from the debris so the momentum stays the same
+        let remnant_velocity = velocity - kick_sum / remnant;
+        let mut pieces = vec![SpaceBody::new(
+            (center.x, center.y),
+            remnant,
+            remnant_radius,
+            remnant_velocity.x,
+            remnant_velocity.y,
+            false,
+            color,
+            0,
+        )];
+        pieces[0].charge = merged.charge * remnant / total;
+        pieces.append(&mut debris);
+        //the debris flies straight out from the centre so carries no angular momentum
+        //around it, which leaves all of it for the pieces to share as spin
+        let inertia: f32 = pieces.iter().map(SpaceBody::moment_of_inertia).sum();
+        let spin = merged.spin_angular_momentum() / inertia;
+        for piece in pieces.iter_mut() {
+            piece.spin = spin;
+        }
+        pieces
     }
     pub fn do_collisions(&mut self) {
         if self.bodies.is_empty() || self.bodies.len() == 1 {
@@ -235,69 +555,362 @@ impl<'a> WorldSpace<'a> {
         let mut to_remove = BTreeSet::new();
         let mut to_push = Vec::new();
         let t = self.bodies.len();
+        //where in `to_push` the focused body ends up, if it was in a collision
         let mut new_focused = None;
+        //ids of the bodies that hit, how fast, and where in `to_push` what is left of them is
+        let mut merged_ids = Vec::new();
         for a in 0..t {
             for b in 0..t {
+                let d = self
+                    .boundary
+                    .separation(self.bodies[a].pos2f(), self.bodies[b].pos2f());
                 if a != b
                     && (self.bodies[a].radius + self.bodies[b].radius).powi(2)
-                        > (self.bodies[a].x - self.bodies[b].x).powi(2)
-                            + (self.bodies[a].y - self.bodies[b].y).powi(2)
+                        > d.x * d.x + d.y * d.y
                     && !to_remove.contains(&a)
                     && !to_remove.contains(&b)
                 {
                     to_remove.insert(a);
                     to_remove.insert(b);
-                    let p = self.collide(a, b);
-                    if !to_push.contains(&p) {
-                        if let Some(idx) = self.focused_idx {
-                            if a == idx {
-                                new_focused = Some(to_push.len() + self.bodies.len() - 2);
-                            }
-                            if b == idx {
-                                new_focused = Some(to_push.len() + self.bodies.len() - 2);
-                            }
-                        }
-                        to_push.push(p);
-                    } else if let Some(idx) = self.focused_idx {
-                        if a == idx {
-                            new_focused = Some(
-                                to_push.iter().position(|x| x == &p).unwrap() + self.bodies.len()
-                                    - 1,
-                            );
-                        }
-                        if b == idx {
-                            new_focused = Some(
-                                to_push.iter().position(|x| x == &p).unwrap() + self.bodies.len()
-                                    - 1,
-                            );
-                        }
+                    if self.focused_idx == Some(a) || self.focused_idx == Some(b) {
+                        new_focused = Some(to_push.len());
                     }
+                    let closing = self.bodies[a].velocity() - self.bodies[b].velocity();
+                    let speed = (closing.x * closing.x + closing.y * closing.y).sqrt();
+                    let pieces = self.collide(a, b);
+                    merged_ids.push((
+                        self.bodies[a].id,
+                        self.bodies[b].id,
+                        speed,
+                        to_push.len()..to_push.len() + pieces.len(),
+                    ));
+                    to_push.extend(pieces);
                 }
             }
         }
+        if to_remove.is_empty() {
+            return;
+        }
         for p in to_remove.iter().enumerate() {
             self.bodies.remove(p.1 - p.0);
         }
-        let mut q = self.bodies.len();
-        for mut i in to_push {
-            if let Some(idx) = new_focused {
-                if idx == i.index {
-                    self.focused_idx = Some(q);
+        let start = self.bodies.len();
+        self.focused_idx = match (new_focused, self.focused_idx) {
+            (Some(offset), _) => Some(start + offset),
+            //bodies before it were removed, so it has moved down
+            (None, Some(idx)) => Some(idx - to_remove.range(..idx).count()),
+            (None, None) => None,
+        };
+        for (q, mut body) in (start..).zip(to_push) {
+            body.index = q;
+            body.id = self.take_id();
+            self.bodies.push(body);
+        }
+        let time = self.sim_time;
+        for (a, b, speed, pieces) in merged_ids {
+            let products: Vec<u64> = self.bodies[start + pieces.start..start + pieces.end]
+                .iter()
+                .map(|body| body.id)
+                .collect();
+            //links follow the bodies into the largest piece left
+            self.relink(a, products[0]);
+            self.relink(b, products[0]);
+            self.emit(SimEvent::Collided { a, b, speed, time });
+            self.emit(SimEvent::Merged {
+                a,
+                b,
+                products,
+                time,
+            });
+        }
+    }
+    //breaks up bodies inside the roche limit of a much heavier one into `fragment_count`
+    //equal pieces with the same total mass and momentum, lined up towards what broke them
+    fn do_tidal_disruption(&mut self) {
+        if !self.tidal_disruption {
+            return;
+        }
+        let mut broken = Vec::new();
+        for (i, body) in self.bodies.iter().enumerate() {
+            if body.rubble || body.immovable {
+                continue;
+            }
+            let primary = self.bodies.iter().find(|other| {
+                let d = self.boundary.separation(body.pos2f(), other.pos2f());
+                other.mass > body.mass * MIN_TIDAL_MASS_RATIO
+                    && d.x * d.x + d.y * d.y
+                        < roche_limit(other.mass, other.radius, body.mass, body.radius).powi(2)
+            });
+            if let Some(primary) = primary {
+                let toward = self.boundary.separation(body.pos2f(), primary.pos2f());
+                let distance = (toward.x * toward.x + toward.y * toward.y).sqrt();
+                broken.push((i, toward / distance.max(f32::EPSILON)));
+            }
+        }
+        let count = self.fragment_count;
+        //from the back, so the indices of the ones still to go don't move
+        for (i, direction) in broken.into_iter().rev() {
+            let body = &self.bodies[i];
+            let mass = body.mass / count as f32;
+            //same total volume, so the density doesn't change
+            let radius = body.radius / (count as f32).cbrt();
+            let center = Vector2f::new(body.x, body.y);
+            let (xv, yv, color) = (body.xv, body.yv, body.shape.fill_color());
+            let charge = body.charge / count as f32;
+            //the pieces turn together like the body did, going round its centre as well as
+            //spinning, which is slower for the same angular momentum
+            let offset = |k: u32| (k as f32 - (count - 1) as f32 / 2.0) * FRAGMENT_SPACING * radius;
+            let inertia: f32 = (0..count)
+                .map(|k| INERTIA_FACTOR * mass * radius * radius + mass * offset(k).powi(2))
+                .sum();
+            let spin = body.spin_angular_momentum() / inertia;
+            let was_focused = self.focused_idx == Some(i);
+            //the first fragment gets the next id, and keeps what the body was linked to
+            let (old, new) = (body.id, self.next_id);
+            self.relink(old, new);
+            self.remove_body(i, Removal::TornApart);
+            if let Some(focused) = self.focused_idx {
+                if focused > i {
+                    self.focused_idx = Some(focused - 1);
                 }
             }
-            i.index = q;
-            self.bodies.push(i);
-            q += 1;
+            if was_focused {
+                self.focused_idx = Some(self.bodies.len());
+            }
+            for k in 0..count {
+                let along = direction * offset(k);
+                let position = center + along;
+                let mut fragment = SpaceBody::new(
+                    (position.x, position.y),
+                    mass,
+                    radius,
+                    xv - spin * along.y,
+                    yv + spin * along.x,
+                    false,
+                    color,
+                    self.bodies.len(),
+                );
+                fragment.rubble = true;
+                fragment.charge = charge;
+                fragment.spin = spin;
+                fragment.id = self.take_id();
+                let (id, time) = (fragment.id, self.sim_time);
+                self.bodies.push(fragment);
+                self.emit(SimEvent::Spawned { id, time });
+            }
         }
     }
     pub fn clear_bodies(&mut self) {
         self.bodies = Vec::new();
+        self.next_id = 0;
+        for trigger in self.triggers.iter_mut() {
+            trigger.reset();
+        }
+        self.particles.clear();
+        self.drop_links();
         self.focused_idx = None;
+        self.emit(SimEvent::Replaced {
+            bodies: 0,
+            time: self.sim_time,
+        });
+    }
+    //links are between particular bodies, so they go when the bodies are all swapped out
+    fn drop_links(&mut self) {
+        self.constraints.clear();
+    }
+    //points every link on body `old` at body `new` instead, dropping any that
+    //would then join a body to itself
+    fn relink(&mut self, old: u64, new: u64) {
+        let swap = |id: &mut u64| {
+            if *id == old {
+                *id = new;
+            }
+        };
+        for constraint in &mut self.constraints {
+            swap(&mut constraint.a);
+            swap(&mut constraint.b);
+        }
+        self.constraints.retain(|c| c.a != c.b);
+    }
+    //drops every link on body `id`
+    fn unlink(&mut self, id: u64) {
+        self.constraints.retain(|c| c.a != id && c.b != id);
+    }
+    //snaps links stretched past their limit, checked before rods are put right
+    fn break_constraints(&mut self) {
+        let (bodies, boundary) = (&self.bodies, &self.boundary);
+        self.constraints
+            .retain(|constraint| !constraint.is_broken(bodies, boundary));
+    }
+    //bounces, wraps or takes away bodies and particles that have gone past the boundary
+    fn apply_boundary(&mut self) {
+        let boundary = self.boundary;
+        match boundary.kind {
+            BoundaryKind::None => {}
+            BoundaryKind::Reflective => {
+                for planet in self.bodies.iter_mut() {
+                    let (position, velocity) =
+                        boundary.reflect(planet.pos2f(), planet.velocity(), planet.radius);
+                    planet.x = position.x;
+                    planet.y = position.y;
+                    planet.xv = velocity.x;
+                    planet.yv = velocity.y;
+                }
+                for particle in self.particles.iter_mut() {
+                    let (position, velocity) = boundary.reflect(
+                        Vector2f::new(particle.x, particle.y),
+                        Vector2f::new(particle.xv, particle.yv),
+                        0.0,
+                    );
+                    particle.x = position.x;
+                    particle.y = position.y;
+                    particle.xv = velocity.x;
+                    particle.yv = velocity.y;
+                }
+            }
+            BoundaryKind::Periodic => {
+                for planet in self.bodies.iter_mut() {
+                    let wrapped = boundary.wrap(planet.pos2f());
+                    //the previous position jumps with it, so it isn't drawn sliding across
+                    planet.prev_x += wrapped.x - planet.x;
+                    planet.prev_y += wrapped.y - planet.y;
+                    planet.x = wrapped.x;
+                    planet.y = wrapped.y;
+                }
+                for particle in self.particles.iter_mut() {
+                    let wrapped = boundary.wrap(Vector2f::new(particle.x, particle.y));
+                    particle.x = wrapped.x;
+                    particle.y = wrapped.y;
+                    particle.save_previous_position();
+                }
+            }
+            BoundaryKind::Absorbing => {
+                self.particles
+                    .retain(|particle| boundary.contains(Vector2f::new(particle.x, particle.y)));
+                //from the back, so the indices of the ones still to go don't move
+                for i in (0..self.bodies.len()).rev() {
+                    let planet = &self.bodies[i];
+                    if boundary.contains(planet.pos2f()) {
+                        continue;
+                    }
+                    self.absorbed.push_back(Absorbed {
+                        id: planet.id,
+                        mass: planet.mass,
+                        position: planet.pos2f(),
+                        velocity: planet.velocity(),
+                        time: self.sim_time,
+                    });
+                    if self.absorbed.len() > MAX_ABSORBED {
+                        self.absorbed.pop_front();
+                    }
+                    match self.focused_idx {
+                        Some(focused) if focused == i => self.focused_idx = None,
+                        Some(focused) if focused > i => self.focused_idx = Some(focused - 1),
+                        _ => {}
+                    }
+                    self.remove_body(i, Removal::LeftWorld);
+                }
+            }
+        }
+    }
+    //moves the ends of every rod back to its length along it, the lighter end further so the
+    //centre of mass stays put, then takes away the speed they had along it
+    fn enforce_rods(&mut self) {
+        let weight = |body: &SpaceBody| {
+            if body.immovable {
+                0.0
+            } else {
+                1.0 / body.mass
+            }
+        };
+        for _ in 0..ROD_ITERATIONS {
+            for constraint in &self.constraints {
+                if constraint.link != Link::Rod {
+                    continue;
+                }
+                let (a, b) = match constraint.ends(&self.bodies) {
+                    Some(ends) => ends,
+                    None => continue,
+                };
+                let (weight_a, weight_b) = (weight(&self.bodies[a]), weight(&self.bodies[b]));
+                let total = weight_a + weight_b;
+                let d = self
+                    .boundary
+                    .separation(self.bodies[a].pos2f(), self.bodies[b].pos2f());
+                let distance = (d.x * d.x + d.y * d.y).sqrt();
+                if total <= 0.0 || distance <= 0.0 {
+                    continue;
+                }
+                let along = d / distance;
+                let closing = self.bodies[b].velocity() - self.bodies[a].velocity();
+                let shift = along * ((distance - constraint.length) / total);
+                let impulse = along * ((closing.x * along.x + closing.y * along.y) / total);
+                let body_a = &mut self.bodies[a];
+                body_a.x += shift.x * weight_a;
+                body_a.y += shift.y * weight_a;
+                body_a.xv += impulse.x * weight_a;
+                body_a.yv += impulse.y * weight_a;
+                let body_b = &mut self.bodies[b];
+                body_b.x -= shift.x * weight_b;
+                body_b.y -= shift.y * weight_b;
+                body_b.xv -= impulse.x * weight_b;
+                body_b.yv -= impulse.y * weight_b;
+            }
+        }
+    }
+    fn take_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id - 1
+    }
+    //gravity as the world's settings have it, for the force models to use
+    fn gravity_model(&self) -> Gravity {
+        Gravity {
+            constant: self.gravity,
+            law: self.force_law,
+        }
+    }
+    //removes particles that are inside a body
+    fn absorb_particles(&mut self) {
+        if !self.absorb_particles {
+            return;
+        }
+        let (bodies, boundary) = (&self.bodies, &self.boundary);
+        self.particles.retain(|particle| {
+            !bodies.iter().any(|body| {
+                let d = boundary.separation(body.pos2f(), Vector2f::new(particle.x, particle.y));
+                d.x * d.x + d.y * d.y < body.radius * body.radius
+            })
+        });
+    }
+    //particles are pulled by the bodies where they are before the bodies move, the same as
+    //bodies are by each other, then move like bodies do
+    fn update_particles(&mut self) {
+        let gravity = self.gravity_model();
+        for particle in self.particles.iter_mut() {
+            let position = Vector2f::new(particle.x, particle.y);
+            let velocity = Vector2f::new(particle.xv, particle.yv);
+            let mut acceleration = Vector2f::new(0.0, 0.0);
+            for spec in &self.forces {
+                acceleration += spec.model(&gravity).accelerate_particle(
+                    &self.bodies,
+                    &self.boundary,
+                    position,
+                    velocity,
+                );
+            }
+            particle.xv += acceleration.x * self.dt;
+            particle.yv += acceleration.y * self.dt;
+            particle.x += particle.xv * self.dt;
+            particle.y += particle.yv * self.dt;
+        }
     }
     fn update_trails(&mut self) {
+        if !self.trails_enabled {
+            return;
+        }
         let mut temp = 0;
         for i in 0..self.trails.len() {
-            if self.trails[i].update() {
+            if self.trails[i].update(self.trail_length) {
                 temp += 1;
             }
         }
@@ -314,35 +927,30 @@ impl<'a> WorldSpace<'a> {
     }
     fn draw_trails(&mut self, target: &mut dyn RenderTarget) {
         for point in &mut self.trails {
-            point.draw(target, self.cam_pos);
+            point.draw(target, self.cam_pos, self.zoom, self.view_size / 2.0);
         }
     }
+    //adds up what every force model does to each body
     fn update_acceleration(&mut self) {
-        let len = self.bodies.len();
-        for i in 0..len {
-            let mut ax = 0.0;
-            let mut ay = 0.0;
-            let planet = &self.bodies[i];
-            for b in 0..len {
-                let other = &self.bodies[b];
-                let error_margin = 1.0;
-                if (other.x - planet.x).abs() > error_margin
-                    && (other.y - planet.y).abs() > error_margin
-                {
-                    let dx = other.x - planet.x;
-                    let dy = other.y - planet.y;
-                    let squared = dx * dx + dy * dy;
-                    let f = (self.gravity * other.mass) / (squared.abs());
-                    ax += dx * f;
-                    ay += dy * f;
-                }
-            }
-            let mut planet_mut = self.bodies.get_mut(i).unwrap();
-            planet_mut.ax = ax;
-            planet_mut.ay = ay;
+        let gravity = self.gravity_model();
+        let mut accelerations = vec![Vector2f::new(0.0, 0.0); self.bodies.len()];
+        for spec in &self.forces {
+            spec.model(&gravity)
+                .accelerate(&self.bodies, &self.boundary, &mut accelerations);
+        }
+        for spring in self.constraints.iter().filter_map(Constraint::spring) {
+            spring.accelerate(&self.bodies, &self.boundary, &mut accelerations);
+        }
+        for (planet, acceleration) in self.bodies.iter_mut().zip(accelerations) {
+            planet.ax = acceleration.x;
+            planet.ay = acceleration.y;
         }
     }
-    pub fn with_bodies(bodies: Vec<SpaceBody>) -> WorldSpace {
+    pub fn with_bodies(mut bodies: Vec<SpaceBody>) -> WorldSpace {
+        for (id, planet) in (0..).zip(bodies.iter_mut()) {
+            planet.id = id;
+        }
+        let next_id = bodies.len() as u64;
         WorldSpace {
             bodies,
             gravity: 70.0,
@@ -352,19 +960,117 @@ impl<'a> WorldSpace<'a> {
             stopped: false,
             cam_pos: Vector2f::new(WINDOW_SIZE.0 / 2.0, WINDOW_SIZE.1 * 0.5),
             focused_idx: None,
+            trail_length: DEATH_AGE,
+            zoom: 1.0,
+            substeps: 1,
+            sim_time: 0.0,
+            tick_rate: DEFAULT_TICK_RATE,
+            trails_enabled: true,
+            force_law: ForceLaw::default(),
+            view_size: Vector2f::new(WINDOW_SIZE.0, WINDOW_SIZE.1),
+            particles: Vec::new(),
+            absorb_particles: true,
+            tidal_disruption: false,
+            fragment_count: DEFAULT_FRAGMENTS,
+            show_hill_sphere: false,
+            fragmentation: false,
+            forces: default_forces(),
+            constraints: Vec::new(),
+            boundary: Boundary::default(),
+            absorbed: VecDeque::new(),
+            escape_radius: DEFAULT_ESCAPE_RADIUS,
+            events: VecDeque::new(),
+            subscribers: Subscribers::default(),
+            events_emitted: 0,
+            triggers: Vec::new(),
+            finished: false,
+            trajectory: None,
+            next_id,
         }
     }
     fn draw<'b: 'shader, 'texture, 'shader, 'shader_texture>(
         &'b mut self,
         target: &mut dyn RenderTarget,
         states: &RenderStates<'texture, 'shader, 'shader_texture>,
+        alpha: f32,
     ) {
         self.draw_trails(target);
+        let (cam_pos, zoom, screen_center) = (self.cam_pos, self.zoom, self.view_size / 2.0);
+        self.boundary
+            .draw(target, |world| (world - cam_pos) * zoom + screen_center);
+        draw_particles(
+            &self.particles,
+            target,
+            self.cam_pos,
+            self.zoom,
+            self.view_size / 2.0,
+            alpha,
+        );
+        self.draw_links(target);
         for planet in &self.bodies {
             planet.shape.draw(target, *states);
         }
+        self.draw_spin_markers(target, alpha);
+    }
+    //a line from the middle of each body to its edge, which turns as the body spins
+    fn draw_spin_markers(&self, target: &mut dyn RenderTarget, alpha: f32) {
+        let mut vertices = Vec::new();
+        for planet in &self.bodies {
+            let radius = planet.shape.radius();
+            if radius < MIN_MARKER_RADIUS {
+                continue;
+            }
+            let fill = planet.shape.fill_color();
+            //dark on light bodies and light on dark ones so it always shows
+            let brightness = 0.299 * fill.r as f32 + 0.587 * fill.g as f32 + 0.114 * fill.b as f32;
+            let color = if brightness > 128.0 {
+                Color::rgba(0, 0, 0, 160)
+            } else {
+                Color::rgba(255, 255, 255, 160)
+            };
+            let center = planet.shape.position() + Vector2f::new(radius, radius);
+            let (sin, cos) = planet.interpolated_angle(alpha).sin_cos();
+            vertices.push(Vertex::with_pos_color(center, color));
+            vertices.push(Vertex::with_pos_color(
+                center + Vector2f::new(cos, sin) * radius,
+                color,
+            ));
+        }
+        target.draw_primitives(&vertices, PrimitiveType::Lines, RenderStates::default());
     }
-    pub fn serialize<T: AsRef<Path>>(self, p: T) -> Result<(), Box<dyn Error>> {
+    //a line between the ends of every spring and link, drawn from the shapes so it matches
+    //them. rods are brighter than springs
+    fn draw_links(&self, target: &mut dyn RenderTarget) {
+        let spring_color = Color::rgba(200, 200, 200, 160);
+        let rod_color = Color::rgb(235, 235, 235);
+        let center = |id: u64| {
+            self.bodies.iter().find(|body| body.id == id).map(|body| {
+                let radius = body.shape.radius();
+                (
+                    body.shape.position() + Vector2f::new(radius, radius),
+                    body.pos2f(),
+                )
+            })
+        };
+        let links = self.constraints.iter().map(|c| {
+            let color = match c.link {
+                Link::Rod => rod_color,
+                Link::Spring { .. } => spring_color,
+            };
+            (c.a, c.b, color)
+        });
+        let mut vertices = Vec::new();
+        for (a, b, color) in links {
+            if let (Some((a, a_pos)), Some((_, b_pos))) = (center(a), center(b)) {
+                //towards the nearest copy of b, which is b itself unless the world wraps
+                let b = a + self.boundary.separation(a_pos, b_pos) * self.zoom;
+                vertices.push(Vertex::with_pos_color(a, color));
+                vertices.push(Vertex::with_pos_color(b, color));
+            }
+        }
+        target.draw_primitives(&vertices, PrimitiveType::Lines, RenderStates::default());
+    }
+    pub fn serialize<T: AsRef<Path>>(&self, p: T) -> Result<(), Box<dyn Error>> {
         let serializable = WorldSpaceSerializable::from(self);
         let serialized = serde_json::to_string(&serializable)?;
         File::create(p)?.write_all(serialized.as_bytes())?;
@@ -375,23 +1081,530 @@ impl<'a> WorldSpace<'a> {
         let space = serde_json::from_str::<WorldSpaceSerializable>(&raw)?;
         Ok(WorldSpace::from(space))
     }
+    pub fn gravity(&self) -> f32 {
+        self.gravity
+    }
+    pub fn set_gravity(&mut self, gravity: f32) {
+        self.changed("gravity", self.gravity, gravity);
+        self.gravity = gravity;
+    }
+    pub fn dt(&self) -> Time {
+        self.dt
+    }
+    pub fn set_dt(&mut self, dt: Time) {
+        self.changed("dt", self.dt, dt);
+        self.dt = dt;
+    }
+    pub fn trail_length(&self) -> usize {
+        self.trail_length
+    }
+    pub fn set_trail_length(&mut self, trail_length: usize) {
+        self.trail_length = trail_length;
+    }
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+    pub fn set_zoom(&mut self, zoom: f32) {
+        if zoom > 0.0 {
+            self.zoom = zoom;
+        }
+    }
+    pub fn substeps(&self) -> u32 {
+        self.substeps
+    }
+    pub fn set_substeps(&mut self, substeps: u32) {
+        let substeps = substeps.clamp(1, MAX_SUBSTEPS);
+        self.changed("substeps", self.substeps, substeps);
+        self.substeps = substeps;
+    }
+    //physics ticks per second of real time
+    pub fn tick_rate(&self) -> f32 {
+        self.tick_rate
+    }
+    pub fn set_tick_rate(&mut self, tick_rate: f32) {
+        let tick_rate = tick_rate.clamp(MIN_TICK_RATE, MAX_TICK_RATE);
+        self.changed("tick_rate", self.tick_rate, tick_rate);
+        self.tick_rate = tick_rate;
+    }
+    pub fn force_law(&self) -> ForceLaw {
+        self.force_law
+    }
+    pub fn set_force_law(&mut self, force_law: ForceLaw) {
+        self.changed("force_law", self.force_law.name(), force_law.name());
+        self.force_law = force_law;
+    }
+    //swaps every body for `bodies` and drops the particles, starting the clock and trails over
+    pub fn replace_bodies(&mut self, mut bodies: Vec<SpaceBody<'a>>) {
+        //nothing refers to the old bodies any more, so ids can start over and match indices
+        self.next_id = 0;
+        for trigger in self.triggers.iter_mut() {
+            trigger.reset();
+        }
+        for (i, planet) in bodies.iter_mut().enumerate() {
+            planet.index = i;
+            planet.id = self.take_id();
+        }
+        self.bodies = bodies;
+        self.trails.clear();
+        self.particles.clear();
+        self.drop_links();
+        self.sim_time = 0.0;
+        self.focused_idx = None;
+        self.cam_pos = self.stats().center_of_mass;
+        self.emit(SimEvent::Replaced {
+            bodies: self.bodies.len(),
+            time: 0.0,
+        });
+    }
+    pub fn trails_enabled(&self) -> bool {
+        self.trails_enabled
+    }
+    //turning trails off also gets rid of the ones already drawn
+    pub fn set_trails_enabled(&mut self, trails_enabled: bool) {
+        self.trails_enabled = trails_enabled;
+        if !trails_enabled {
+            self.trails.clear();
+        }
+    }
+    pub fn sim_time(&self) -> f64 {
+        self.sim_time
+    }
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+    pub fn screen_to_world(&self, screen: Vector2f) -> Vector2f {
+        (screen - self.view_size / 2.0) / self.zoom + self.cam_pos
+    }
+    pub fn world_to_screen(&self, world: Vector2f) -> Vector2f {
+        (world - self.cam_pos) * self.zoom + self.view_size / 2.0
+    }
+    //the size of the window the world is drawn into
+    pub fn set_view_size(&mut self, view_size: Vector2f) {
+        self.view_size = view_size;
+    }
     pub fn switch_stopped(&mut self) {
         self.stopped = !self.stopped;
     }
-    pub fn advance(&mut self, target: &mut dyn RenderTarget, states: &RenderStates) {
+    fn step(&mut self) {
+        self.do_tidal_disruption();
+        self.do_collisions();
+        self.absorb_particles();
+        self.update_acceleration();
+        self.update_particles();
+        self.update_positions();
+        self.update_time();
+        self.break_constraints();
+        self.enforce_rods();
+        self.apply_boundary();
+        self.update_trails();
+        self.sim_time += self.dt as f64;
+        self.update_fates();
+    }
+    //works out what every body is doing, and tells about the ones that just escaped or were
+    //just captured
+    fn update_fates(&mut self) {
+        let time = self.sim_time;
+        let mut happened = Vec::new();
+        let fates = fates(self);
+        for (planet, fate) in self.bodies.iter_mut().zip(fates) {
+            let changed = planet.fate.is_some_and(|old| old != fate);
+            planet.fate = Some(fate);
+            if !changed {
+                continue;
+            }
+            match fate {
+                Fate::Escaping => happened.push(SimEvent::Escaped {
+                    id: planet.id,
+                    time,
+                }),
+                Fate::Captured(by) => happened.push(SimEvent::Captured {
+                    id: planet.id,
+                    by,
+                    time,
+                }),
+                Fate::Bound => {}
+            }
+        }
+        for event in happened {
+            self.emit(event);
+        }
+    }
+    //passes an event on to the trajectory log, the subscribers and whoever takes the events
+    fn emit(&mut self, event: SimEvent) {
+        if let Some(log) = self.trajectory.as_mut() {
+            if let Err(e) = log.event(&event) {
+                eprintln!("Stopped writing to {}: {}", log.path(), e);
+                self.trajectory = None;
+            }
+        }
+        self.subscribers.notify(&event);
+        self.events_emitted += 1;
+        self.events.push_back(event);
+        while self.events.len() > MAX_EVENTS {
+            self.events.pop_front();
+        }
+    }
+    //tells about a setting changing, if it really did
+    fn changed<T: PartialEq + ToString>(&mut self, name: &'static str, old: T, new: T) {
+        if old != new {
+            self.emit(SimEvent::ParameterChanged {
+                name,
+                value: new.to_string(),
+                time: self.sim_time,
+            });
+        }
+    }
+    //sets off the triggers whose conditions just started to hold, `emitted_before` being how
+    //many events there had been before the step. returns whether any went off
+    fn check_triggers(&mut self, emitted_before: u64) -> bool {
+        if self.triggers.is_empty() {
+            return false;
+        }
+        let new_events = (self.events_emitted - emitted_before).min(self.events.len() as u64);
+        let collided = self
+            .events
+            .iter()
+            .rev()
+            .take(new_events as usize)
+            .any(|event| matches!(event, SimEvent::Collided { .. }));
+        let wants_energy = self
+            .triggers
+            .iter()
+            .any(|t| matches!(t.condition, Condition::EnergyError { .. }));
+        let energy = if wants_energy {
+            self.total_energy()
+        } else {
+            0.0
+        };
+        let (bodies, boundary, sim_time) = (&self.bodies, &self.boundary, self.sim_time);
+        let mut fired = Vec::new();
+        for trigger in self.triggers.iter_mut() {
+            let holds = match trigger.condition {
+                Condition::Collision => collided,
+                Condition::Time { time } => sim_time >= time,
+                Condition::Escape { id } => bodies
+                    .iter()
+                    .any(|body| body.id == id && body.fate == Some(Fate::Escaping)),
+                Condition::EnergyError { limit } => {
+                    let reference = *trigger.reference_energy.get_or_insert(energy);
+                    ((energy - reference) / reference.abs().max(f64::EPSILON)).abs() > limit
+                }
+                Condition::Near { a, b, distance } => {
+                    let find = |id| bodies.iter().find(|body| body.id == id);
+                    match (find(a), find(b)) {
+                        (Some(a), Some(b)) => {
+                            let d = boundary.separation(a.pos2f(), b.pos2f());
+                            d.x * d.x + d.y * d.y < distance * distance
+                        }
+                        _ => false,
+                    }
+                }
+            };
+            if holds && !trigger.held {
+                fired.push(*trigger);
+            }
+            trigger.held = holds;
+        }
+        let any_fired = !fired.is_empty();
+        for trigger in fired {
+            self.stopped = true;
+            if trigger.action == Action::Stop {
+                self.finished = true;
+            }
+            self.emit(SimEvent::Triggered {
+                trigger: trigger.describe(),
+                time: self.sim_time,
+            });
+        }
+        any_fired
+    }
+    pub fn triggers(&self) -> &[Trigger] {
+        &self.triggers
+    }
+    pub fn add_trigger(&mut self, trigger: Trigger) {
+        self.triggers.push(trigger);
+    }
+    pub fn remove_trigger(&mut self, index: usize) -> Option<Trigger> {
+        if index < self.triggers.len() {
+            Some(self.triggers.remove(index))
+        } else {
+            None
+        }
+    }
+    pub fn clear_triggers(&mut self) {
+        self.triggers.clear();
+    }
+    //whether a stop trigger has gone off
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+    //kinetic and spin energy of every body plus the gravitational potential energy of every
+    //pair, which other force models don't add to. under 1/r only changes in it mean anything
+    pub fn total_energy(&self) -> f64 {
+        let mut energy = 0.0;
+        for (i, a) in self.bodies.iter().enumerate() {
+            energy += 0.5 * (a.mass * (a.xv * a.xv + a.yv * a.yv)) as f64
+                + 0.5 * (a.moment_of_inertia() * a.spin * a.spin) as f64;
+            for b in &self.bodies[i + 1..] {
+                let d = self.boundary.separation(a.pos2f(), b.pos2f());
+                //pairs that don't pull on each other have no energy between them either
+                if !pulls(d) {
+                    continue;
+                }
+                let distance = (d.x * d.x + d.y * d.y).sqrt();
+                energy +=
+                    (a.mass * self.force_law.potential(self.gravity, b.mass, distance)) as f64;
+            }
+        }
+        energy
+    }
+    //everything that happened since the last time this was called
+    pub fn take_events(&mut self) -> Vec<SimEvent> {
+        self.events.drain(..).collect()
+    }
+    //calls `callback` with every event from now on, as it happens
+    pub fn subscribe(&mut self, callback: Callback<'a>) {
+        self.subscribers.add(callback);
+    }
+    //moves the subscribers over from `old`, for when this world takes its place
+    pub fn keep_subscribers(&mut self, old: &mut WorldSpace<'a>) {
+        self.subscribers = std::mem::take(&mut old.subscribers);
+    }
+    //starts writing where every body is after each tick to `path`, replacing any older log
+    pub fn start_recording(&mut self, path: &str) -> io::Result<()> {
+        self.stop_recording();
+        self.trajectory = Some(TrajectoryLog::create(path)?);
+        Ok(())
+    }
+    //stops the trajectory log, giving back where it was written if there was one
+    pub fn stop_recording(&mut self) -> Option<String> {
+        let log = self.trajectory.take()?;
+        let path = log.path().to_string();
+        if let Err(e) = log.finish() {
+            eprintln!("Couldn't finish writing {}: {}", path, e);
+        }
+        Some(path)
+    }
+    pub fn recording(&self) -> Option<&str> {
+        self.trajectory.as_ref().map(|log| log.path())
+    }
+    pub fn escape_radius(&self) -> f32 {
+        self.escape_radius
+    }
+    pub fn set_escape_radius(&mut self, escape_radius: f32) {
+        self.changed("escape_radius", self.escape_radius, escape_radius);
+        self.escape_radius = escape_radius;
+    }
+    fn save_previous_positions(&mut self) {
+        for planet in &mut self.bodies {
+            planet.prev_x = planet.x;
+            planet.prev_y = planet.y;
+            planet.prev_angle = planet.angle;
+        }
+        for particle in &mut self.particles {
+            particle.save_previous_position();
+        }
+    }
+    //runs a single step, only while the simulation is stopped
+    pub fn step_once(&mut self) {
+        if self.stopped {
+            self.save_previous_positions();
+            self.advance(1);
+        }
+    }
+    //runs `steps` steps straight away, whether or not the simulation is stopped, unless a
+    //trigger goes off first. returns how many it ran
+    pub fn run_steps(&mut self, steps: u32) -> u32 {
+        self.save_previous_positions();
+        self.advance(steps)
+    }
+    //runs up to `steps` steps, stopping after one that sets off a trigger, then writes where
+    //everything got to in the trajectory log. returns how many it ran
+    fn advance(&mut self, steps: u32) -> u32 {
+        let mut ran = 0;
+        while ran < steps {
+            let emitted = self.events_emitted;
+            self.step();
+            ran += 1;
+            if self.check_triggers(emitted) {
+                break;
+            }
+        }
+        if let Some(log) = self.trajectory.as_mut() {
+            if let Err(e) = log.sample(self.sim_time, &self.bodies) {
+                eprintln!("Stopped writing to {}: {}", log.path(), e);
+                self.trajectory = None;
+            }
+        }
+        ran
+    }
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats {
+            bodies: self.bodies.len(),
+            particles: self.particles.len(),
+            mass: 0.0,
+            center_of_mass: Vector2f::new(0.0, 0.0),
+            momentum: Vector2f::new(0.0, 0.0),
+            kinetic_energy: 0.0,
+            angular_momentum: 0.0,
+        };
+        for planet in &self.bodies {
+            stats.mass += planet.mass;
+            stats.center_of_mass += Vector2f::new(planet.x, planet.y) * planet.mass;
+            stats.momentum += Vector2f::new(planet.xv, planet.yv) * planet.mass;
+            stats.kinetic_energy +=
+                0.5 * planet.mass * (planet.xv * planet.xv + planet.yv * planet.yv);
+        }
+        if stats.mass > 0.0 {
+            stats.center_of_mass /= stats.mass;
+        }
+        //around the centre of mass, so it doesn't change when the world is moved
+        for planet in &self.bodies {
+            stats.angular_momentum += planet.mass
+                * cross(planet.pos2f() - stats.center_of_mass, planet.velocity())
+                + planet.spin_angular_momentum();
+        }
+        stats
+    }
+    //one fixed physics tick, which is `substeps` steps of `dt` each
+    pub fn tick(&mut self) {
+        self.save_previous_positions();
         if !self.stopped {
-            self.do_collisions();
-            self.update_acceleration();
-            self.update_positions();
-            self.update_time();
-            self.update_trails();
+            self.advance(self.substeps);
         }
-        self.update_cam_pos();
-        self.update_planets_shape_pos();
-        self.draw(target, states);
     }
-    pub fn push_body(&mut self, body: SpaceBody<'a>) {
+    //`alpha` is how far between the last two ticks to draw the bodies, from 0 to 1
+    pub fn render(&mut self, target: &mut dyn RenderTarget, states: &RenderStates, alpha: f32) {
+        self.update_cam_pos(alpha);
+        self.update_planets_shape_pos(alpha);
+        self.draw(target, states, alpha);
+    }
+    pub fn push_body(&mut self, mut body: SpaceBody<'a>) {
+        body.id = self.take_id();
+        let (id, time) = (body.id, self.sim_time);
         self.bodies.push(body);
+        self.emit(SimEvent::Spawned { id, time });
+    }
+    pub fn forces(&self) -> &[ForceSpec] {
+        &self.forces
+    }
+    pub fn add_force(&mut self, spec: ForceSpec) {
+        self.forces.push(spec);
+    }
+    pub fn remove_force(&mut self, index: usize) -> Option<ForceSpec> {
+        if index < self.forces.len() {
+            Some(self.forces.remove(index))
+        } else {
+            None
+        }
+    }
+    pub fn clear_forces(&mut self) {
+        self.forces.clear();
+    }
+    pub fn constraints(&self) -> &[Constraint] {
+        &self.constraints
+    }
+    //joins bodies `a` and `b` at the distance they are apart now
+    pub fn add_constraint(
+        &mut self,
+        a: usize,
+        b: usize,
+        link: Link,
+        breaking_stretch: Option<f32>,
+    ) -> Result<Constraint, String> {
+        if a == b {
+            return Err("a body can't be linked to itself".to_string());
+        }
+        let (body_a, body_b) = match (self.bodies.get(a), self.bodies.get(b)) {
+            (Some(body_a), Some(body_b)) => (body_a, body_b),
+            _ => {
+                return Err(format!(
+                    "{} and {} aren't both bodies, there are {}",
+                    a,
+                    b,
+                    self.bodies.len()
+                ))
+            }
+        };
+        //the shortest way between them, which goes across the edges of a periodic world
+        let d = self.boundary.separation(body_a.pos2f(), body_b.pos2f());
+        let constraint = Constraint {
+            a: body_a.id,
+            b: body_b.id,
+            length: (d.x * d.x + d.y * d.y).sqrt(),
+            link,
+            breaking_stretch,
+        };
+        self.constraints.push(constraint);
+        Ok(constraint)
+    }
+    pub fn remove_constraint(&mut self, index: usize) -> Option<Constraint> {
+        if index < self.constraints.len() {
+            Some(self.constraints.remove(index))
+        } else {
+            None
+        }
+    }
+    pub fn clear_constraints(&mut self) {
+        self.constraints.clear();
+    }
+    pub fn boundary(&self) -> Boundary {
+        self.boundary
+    }
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        let describe = |b: Boundary| {
+            format!(
+                "{} ({}, {}) {}x{}",
+                b.kind.name(),
+                b.left,
+                b.top,
+                b.width,
+                b.height
+            )
+        };
+        self.changed("boundary", describe(self.boundary), describe(boundary));
+        self.boundary = boundary;
+    }
+    pub fn absorbed(&self) -> &VecDeque<Absorbed> {
+        &self.absorbed
+    }
+    pub fn tidal_disruption(&self) -> bool {
+        self.tidal_disruption
+    }
+    pub fn set_tidal_disruption(&mut self, tidal_disruption: bool) {
+        self.changed("tidal_disruption", self.tidal_disruption, tidal_disruption);
+        self.tidal_disruption = tidal_disruption;
+    }
+    pub fn fragment_count(&self) -> u32 {
+        self.fragment_count
+    }
+    pub fn set_fragment_count(&mut self, fragment_count: u32) {
+        let fragment_count = fragment_count.clamp(MIN_FRAGMENTS, MAX_FRAGMENTS);
+        self.changed("fragments", self.fragment_count, fragment_count);
+        self.fragment_count = fragment_count;
+    }
+    pub fn fragmentation(&self) -> bool {
+        self.fragmentation
+    }
+    pub fn set_fragmentation(&mut self, fragmentation: bool) {
+        self.changed("fragmentation", self.fragmentation, fragmentation);
+        self.fragmentation = fragmentation;
+    }
+    pub fn show_hill_sphere(&self) -> bool {
+        self.show_hill_sphere
+    }
+    pub fn set_show_hill_sphere(&mut self, show_hill_sphere: bool) {
+        self.show_hill_sphere = show_hill_sphere;
+    }
+    pub fn push_particle(&mut self, particle: TestParticle) {
+        self.particles.push(particle);
+    }
+    pub fn absorb_particles_enabled(&self) -> bool {
+        self.absorb_particles
+    }
+    pub fn set_absorb_particles(&mut self, absorb_particles: bool) {
+        self.changed("absorb_particles", self.absorb_particles, absorb_particles);
+        self.absorb_particles = absorb_particles;
     }
     pub fn prepare_for_gui(&mut self) -> Option<(CircleShape<'a>, usize)> {
         if let Some(index) = self.focused_idx {
@@ -438,14 +1651,27 @@ impl<'a> WorldSpace<'a> {
     }
     pub fn remove_selected(&mut self) {
         if let Some(index) = self.focused_idx {
-            self.remove_body(index);
+            self.remove_body(index, Removal::Deleted);
             if self.bodies.is_empty() {
                 self.focused_idx = None;
             }
         }
     }
-    pub fn remove_body(&mut self, idx: usize) {
-        self.bodies.remove(idx);
+    pub fn recolor_selected(&mut self, color: Color) {
+        if let Some(index) = self.focused_idx {
+            if let Some(planet) = self.bodies.get_mut(index) {
+                planet.shape.set_fill_color(color);
+            }
+        }
+    }
+    pub fn remove_body(&mut self, idx: usize, reason: Removal) {
+        let removed = self.bodies.remove(idx);
+        self.unlink(removed.id);
+        self.emit(SimEvent::Removed {
+            id: removed.id,
+            reason,
+            time: self.sim_time,
+        });
         for planet in &mut self.bodies[idx..] {
             planet.index -= 1;
         }
@@ -488,7 +1714,19 @@ struct BodySerializable {
     radius: f32,
     immovable: bool,
     color_rgb: (u8, u8, u8),
+    #[serde(default = "default_alpha")]
+    alpha: u8,
     index: usize,
+    #[serde(default)]
+    rubble: bool,
+    #[serde(default)]
+    id: Option<u64>,
+    #[serde(default)]
+    charge: f32,
+    #[serde(default)]
+    angle: f32,
+    #[serde(default)]
+    spin: f32,
 }
 #[derive(Debug, Serialize, Deserialize)]
 struct WorldSpaceSerializable {
@@ -496,38 +1734,244 @@ struct WorldSpaceSerializable {
     gravity: f32,
     softening: f32,
     bodies: Vec<BodySerializable>,
+    #[serde(default)]
+    constraints: Vec<Constraint>,
+    #[serde(default)]
+    boundary: Boundary,
+    #[serde(default = "default_escape_radius")]
+    escape_radius: f32,
+    #[serde(default)]
+    triggers: Vec<Trigger>,
     stopped: bool,
     cam_pos: (f32, f32),
     focused_idx: Option<usize>,
+    #[serde(default = "default_trail_length")]
+    trail_length: usize,
+    #[serde(default = "default_zoom")]
+    zoom: f32,
+    #[serde(default = "default_substeps")]
+    substeps: u32,
+    #[serde(default)]
+    sim_time: f64,
+    #[serde(default = "default_tick_rate")]
+    tick_rate: f32,
+    #[serde(default = "default_trails_enabled")]
+    trails_enabled: bool,
+    #[serde(default)]
+    force_law: ForceLaw,
+    #[serde(default)]
+    particles: Vec<ParticleSerializable>,
+    #[serde(default = "default_absorb_particles")]
+    absorb_particles: bool,
+    #[serde(default)]
+    tidal_disruption: bool,
+    #[serde(default = "default_fragment_count")]
+    fragment_count: u32,
+    #[serde(default)]
+    show_hill_sphere: bool,
+    #[serde(default)]
+    fragmentation: bool,
+    #[serde(default = "default_forces")]
+    forces: Vec<ForceSpec>,
+}
+fn default_escape_radius() -> f32 {
+    DEFAULT_ESCAPE_RADIUS
+}
+fn default_forces() -> Vec<ForceSpec> {
+    vec![ForceSpec::Gravity]
+}
+fn default_alpha() -> u8 {
+    255
+}
+fn default_trails_enabled() -> bool {
+    true
+}
+fn default_absorb_particles() -> bool {
+    true
+}
+fn default_fragment_count() -> u32 {
+    DEFAULT_FRAGMENTS
+}
+fn default_tick_rate() -> f32 {
+    DEFAULT_TICK_RATE
+}
+fn default_substeps() -> u32 {
+    1
 }
-impl From<WorldSpace<'_>> for WorldSpaceSerializable {
-    fn from(other: WorldSpace) -> Self {
+fn default_trail_length() -> usize {
+    DEATH_AGE
+}
+fn default_zoom() -> f32 {
+    1.0
+}
+impl From<&WorldSpace<'_>> for WorldSpaceSerializable {
+    fn from(other: &WorldSpace) -> Self {
         WorldSpaceSerializable {
             dt: other.dt,
             gravity: other.gravity,
             softening: other.softening,
-            bodies: other
-                .bodies
-                .into_iter()
-                .map(BodySerializable::from)
-                .collect(),
+            bodies: other.bodies.iter().map(BodySerializable::from).collect(),
+            constraints: other.constraints.clone(),
+            boundary: other.boundary,
+            escape_radius: other.escape_radius,
+            triggers: other.triggers.clone(),
             stopped: other.stopped,
             cam_pos: (other.cam_pos.x, other.cam_pos.y),
             focused_idx: other.focused_idx,
+            trail_length: other.trail_length,
+            zoom: other.zoom,
+            substeps: other.substeps,
+            sim_time: other.sim_time,
+            tick_rate: other.tick_rate,
+            trails_enabled: other.trails_enabled,
+            force_law: other.force_law,
+            particles: other
+                .particles
+                .iter()
+                .map(ParticleSerializable::from)
+                .collect(),
+            absorb_particles: other.absorb_particles,
+            tidal_disruption: other.tidal_disruption,
+            fragment_count: other.fragment_count,
+            show_hill_sphere: other.show_hill_sphere,
+            fragmentation: other.fragmentation,
+            forces: other.forces.clone(),
         }
     }
 }
 impl From<WorldSpaceSerializable> for WorldSpace<'_> {
     fn from(other: WorldSpaceSerializable) -> Self {
+        let mut next_id = other
+            .bodies
+            .iter()
+            .filter_map(|body| body.id)
+            .max()
+            .map_or(0, |id| id + 1);
+        let bodies = other
+            .bodies
+            .into_iter()
+            .map(|body| {
+                let fresh = body.id.is_none();
+                let mut body = SpaceBody::from(body);
+                if fresh {
+                    body.id = next_id;
+                    next_id += 1;
+                }
+                body
+            })
+            .collect();
         WorldSpace {
             dt: other.dt,
             gravity: other.gravity,
             softening: other.softening,
-            bodies: other.bodies.into_iter().map(SpaceBody::from).collect(),
+            bodies,
+            constraints: other.constraints,
+            boundary: other.boundary,
+            absorbed: VecDeque::new(),
+            escape_radius: other.escape_radius,
+            events: VecDeque::new(),
+            subscribers: Subscribers::default(),
+            events_emitted: 0,
+            triggers: other.triggers,
+            finished: false,
+            trajectory: None,
             trails: VecDeque::new(),
             stopped: other.stopped,
             cam_pos: Vector2f::new(other.cam_pos.0, other.cam_pos.1),
             focused_idx: other.focused_idx,
+            trail_length: other.trail_length,
+            zoom: other.zoom,
+            substeps: other.substeps.clamp(1, MAX_SUBSTEPS),
+            sim_time: other.sim_time,
+            tick_rate: other.tick_rate.clamp(MIN_TICK_RATE, MAX_TICK_RATE),
+            trails_enabled: other.trails_enabled,
+            force_law: other.force_law,
+            view_size: Vector2f::new(WINDOW_SIZE.0, WINDOW_SIZE.1),
+            particles: other
+                .particles
+                .into_iter()
+                .map(TestParticle::from)
+                .collect(),
+            absorb_particles: other.absorb_particles,
+            tidal_disruption: other.tidal_disruption,
+            fragment_count: other.fragment_count.clamp(MIN_FRAGMENTS, MAX_FRAGMENTS),
+            show_hill_sphere: other.show_hill_sphere,
+            fragmentation: other.fragmentation,
+            forces: other.forces,
+            next_id,
         }
     }
 }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //two bodies of 100 heading at each other at `speed`, with fragmentation on
+    fn head_on<'a>(speed: f32) -> WorldSpace<'a> {
+        let mut space = WorldSpace::default();
+        space.replace_bodies(vec![
+            SpaceBody::new(
+                (0.0, 0.0),
+                100.0,
+                10.0,
+                speed / 2.0,
+                1.0,
+                false,
+                Color::WHITE,
+                0,
+            ),
+            SpaceBody::new(
+                (19.0, 0.0),
+                100.0,
+                10.0,
+                -speed / 2.0,
+                0.0,
+                false,
+                Color::RED,
+                1,
+            ),
+        ]);
+        space.set_fragmentation(true);
+        space
+    }
+    fn mass_and_momentum(bodies: &[SpaceBody]) -> (f32, Vector2f) {
+        bodies
+            .iter()
+            .fold((0.0, Vector2f::new(0.0, 0.0)), |(m, p), b| {
+                (m + b.mass, p + b.velocity() * b.mass)
+            })
+    }
+    #[test]
+    fn gentle_impacts_merge() {
+        let space = head_on(10.0);
+        let pieces = space.collide(0, 1);
+        assert_eq!(pieces.len(), 1);
+        let (mass, momentum) = mass_and_momentum(&space.bodies);
+        assert!((pieces[0].mass - mass).abs() < 1e-3);
+        assert!((pieces[0].velocity() * pieces[0].mass - momentum).x.abs() < 1e-2);
+        assert!((pieces[0].velocity() * pieces[0].mass - momentum).y.abs() < 1e-2);
+    }
+    #[test]
+    fn hard_impacts_shatter_keeping_mass_and_momentum() {
+        let space = head_on(1000.0);
+        let pieces = space.collide(0, 1);
+        assert!(pieces.len() > 1);
+        assert!(pieces[1..].iter().all(|piece| piece.rubble));
+        let (mass, momentum) = mass_and_momentum(&space.bodies);
+        let (pieces_mass, pieces_momentum) = mass_and_momentum(&pieces);
+        assert!((pieces_mass - mass).abs() < mass * 1e-5);
+        //the debris carries momentum of order mass times speed, so allow for rounding in that
+        let tolerance = mass * 1000.0 * 1e-5;
+        assert!((pieces_momentum.x - momentum.x).abs() < tolerance);
+        assert!((pieces_momentum.y - momentum.y).abs() < tolerance);
+    }
+    #[test]
+    fn merging_keeps_the_centre_of_mass() {
+        let space = head_on(10.0);
+        let (a, b) = (&space.bodies[0], &space.bodies[1]);
+        let center = (a.pos2f() * a.mass + b.pos2f() * b.mass) / (a.mass + b.mass);
+        let merged = space.merge(0, 1);
+        assert!((merged.pos2f() - center).x.abs() < 1e-3);
+        assert!((merged.pos2f() - center).y.abs() < 1e-3);
+    }
+}
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use sfml::{
+    graphics::{Color, RectangleShape, RenderTarget, Shape, Transformable},
+    system::Vector2f,
+};
+
+use crate::WINDOW_SIZE;
+//how many bodies that left through an absorbing boundary are remembered
+pub const MAX_ABSORBED: usize = 100;
+//what happens to things that reach the edge of the world
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BoundaryKind {
+    //there is no edge and bodies fly off forever, like it always was
+    #[default]
+    None,
+    //bodies bounce off the walls
+    Reflective,
+    //bodies leaving one side come back in the other, and everything pulls and hits across
+    //the edges the shortest way round
+    Periodic,
+    //bodies leaving are gone
+    Absorbing,
+}
+impl BoundaryKind {
+    pub const ALL: [BoundaryKind; 4] = [
+        BoundaryKind::None,
+        BoundaryKind::Reflective,
+        BoundaryKind::Periodic,
+        BoundaryKind::Absorbing,
+    ];
+    pub fn name(&self) -> &'static str {
+        match self {
+            BoundaryKind::None => "none",
+            BoundaryKind::Reflective => "reflective",
+            BoundaryKind::Periodic => "periodic",
+            BoundaryKind::Absorbing => "absorbing",
+        }
+    }
+    pub fn from_name(name: &str) -> Option<BoundaryKind> {
+        BoundaryKind::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.name() == name)
+    }
+}
+//the box the world is kept in, in world coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Boundary {
+    pub kind: BoundaryKind,
+    pub left: f32,
+    pub top: f32,
+    pub width: f32,
+    pub height: f32,
+}
+impl Default for Boundary {
+    //the first screenful, which is where the presets put things
+    fn default() -> Self {
+        Boundary {
+            kind: BoundaryKind::None,
+            left: 0.0,
+            top: 0.0,
+            width: WINDOW_SIZE.0,
+            height: WINDOW_SIZE.1,
+        }
+    }
+}
+//what is left of a body that went out through an absorbing boundary
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Absorbed {
+    pub id: u64,
+    pub mass: f32,
+    pub position: Vector2f,
+    pub velocity: Vector2f,
+    pub time: f64,
+}
+impl Boundary {
+    //the way from `from` to `to`, which for a periodic world is to the nearest copy of `to`
+    pub fn separation(&self, from: Vector2f, to: Vector2f) -> Vector2f {
+        let d = to - from;
+        if self.kind != BoundaryKind::Periodic {
+            return d;
+        }
+        Vector2f::new(
+            d.x - self.width * (d.x / self.width).round(),
+            d.y - self.height * (d.y / self.height).round(),
+        )
+    }
+    pub fn contains(&self, position: Vector2f) -> bool {
+        position.x >= self.left
+            && position.x <= self.left + self.width
+            && position.y >= self.top
+            && position.y <= self.top + self.height
+    }
+    //the same point moved back inside a periodic world
+    pub fn wrap(&self, position: Vector2f) -> Vector2f {
+        Vector2f::new(
+            self.left + (position.x - self.left).rem_euclid(self.width),
+            self.top + (position.y - self.top).rem_euclid(self.height),
+        )
+    }
+    //keeps something `radius` big off the walls, turning it round if it was heading into one.
+    //returns the new position and velocity
+    pub fn reflect(
+        &self,
+        position: Vector2f,
+        velocity: Vector2f,
+        radius: f32,
+    ) -> (Vector2f, Vector2f) {
+        let bounce = |p: f32, v: f32, low: f32, high: f32| {
+            //something bigger than the box just sits in the middle of it
+            if high - low < 2.0 * radius {
+                ((low + high) / 2.0, 0.0)
+            } else if p - radius < low {
+                (2.0 * (low + radius) - p, v.abs())
+            } else if p + radius > high {
+                (2.0 * (high - radius) - p, -v.abs())
+            } else {
+                (p, v)
+            }
+        };
+        let (x, xv) = bounce(position.x, velocity.x, self.left, self.left + self.width);
+        let (y, yv) = bounce(position.y, velocity.y, self.top, self.top + self.height);
+        (Vector2f::new(x, y), Vector2f::new(xv, yv))
+    }
+    //an outline of the box, coloured by what it does
+    pub fn draw(
+        &self,
+        target: &mut dyn RenderTarget,
+        world_to_screen: impl Fn(Vector2f) -> Vector2f,
+    ) {
+        let color = match self.kind {
+            BoundaryKind::None => return,
+            BoundaryKind::Reflective => Color::rgba(220, 220, 220, 180),
+            BoundaryKind::Periodic => Color::rgba(120, 170, 255, 180),
+            BoundaryKind::Absorbing => Color::rgba(255, 110, 110, 180),
+        };
+        let top_left = world_to_screen(Vector2f::new(self.left, self.top));
+        let bottom_right = world_to_screen(Vector2f::new(
+            self.left + self.width,
+            self.top + self.height,
+        ));
+        let mut outline = RectangleShape::with_size(bottom_right - top_left);
+        outline.set_position(top_left);
+        outline.set_fill_color(Color::TRANSPARENT);
+        outline.set_outline_thickness(1.0);
+        outline.set_outline_color(color);
+        target.draw(&outline);
+    }
+}
@@ -0,0 +1,190 @@
+use sfml::{
+    graphics::{Color, Font, RenderTarget, Text, Transformable},
+    system::Vector2f,
+};
+use std::time::{Duration, Instant};
+
+use crate::{
+    bodies::WorldSpace,
+    gui::ExamplePlanet,
+    shapes::RoundedRect,
+    widgets::{Command, EventResponse, Flag, Widget, WidgetAction, WidgetEvent, WidgetKind},
+};
+const TEXT_SIZE: u32 = 20;
+const CORNER_RADIUS: f32 = 6.0;
+//how long a button that needs confirming waits for the second click
+const CONFIRM_TIME: Duration = Duration::from_secs(3);
+const CONFIRM_LABEL: &str = "sure?";
+//a labelled button that runs `command` when it is clicked
+#[derive(Debug)]
+pub struct Button<'a> {
+    layer: usize,
+    rect: RoundedRect<'a>,
+    label: String,
+    //shown instead of `label` while the flag is set, like "resume" while paused
+    alt_label: Option<(Flag, String)>,
+    showing_alt: bool,
+    command: Command,
+    confirm: bool,
+    armed_at: Option<Instant>,
+    hovered: bool,
+    pressed: bool,
+}
+impl<'a> Button<'a> {
+    pub fn new<T: Into<Vector2f>>(
+        label: &str,
+        command: Command,
+        layer: usize,
+        dims: T,
+    ) -> Button<'a> {
+        let mut button = Button {
+            layer,
+            rect: RoundedRect::new(
+                CORNER_RADIUS,
+                dims.into(),
+                Vector2f::new(0.0, 0.0),
+                Color::BLACK,
+            ),
+            label: label.to_string(),
+            alt_label: None,
+            showing_alt: false,
+            command,
+            confirm: false,
+            armed_at: None,
+            hovered: false,
+            pressed: false,
+        };
+        button.update_color();
+        button
+    }
+    pub fn with_alt_label(mut self, flag: Flag, label: &str) -> Self {
+        self.alt_label = Some((flag, label.to_string()));
+        self
+    }
+    //the first click only arms the button, and a second one runs the command
+    pub fn needs_confirmation(mut self) -> Self {
+        self.confirm = true;
+        self
+    }
+    fn contains(&self, x: i32, y: i32) -> bool {
+        let (top_left, bottom_right) = self.rect.get_bounds();
+        (x as f32) >= top_left.x
+            && (x as f32) <= bottom_right.x
+            && (y as f32) >= top_left.y
+            && (y as f32) <= bottom_right.y
+    }
+    fn update_color(&mut self) {
+        let color = if self.armed_at.is_some() {
+            Color::rgb(150, 40, 40)
+        } else if self.pressed {
+            Color::rgb(40, 40, 50)
+        } else if self.hovered {
+            Color::rgb(100, 100, 120)
+        } else {
+            Color::rgb(70, 70, 85)
+        };
+        self.rect.set_fill_color(color);
+    }
+    fn shown_label(&self) -> &str {
+        if self.armed_at.is_some() {
+            CONFIRM_LABEL
+        } else {
+            match &self.alt_label {
+                Some((_, alt)) if self.showing_alt => alt,
+                _ => &self.label,
+            }
+        }
+    }
+    fn clicked(&mut self) -> EventResponse {
+        if self.confirm && self.armed_at.is_none() {
+            self.armed_at = Some(Instant::now());
+            return EventResponse::consumed();
+        }
+        self.armed_at = None;
+        EventResponse::Consumed(vec![WidgetAction::Run(self.command)])
+    }
+}
+impl Widget for Button<'_> {
+    fn get_bounds(&self) -> (Vector2f, Vector2f) {
+        self.rect.get_bounds()
+    }
+
+    fn get_layer(&self) -> usize {
+        self.layer
+    }
+
+    fn size(&self) -> Vector2f {
+        self.rect.dimensions
+    }
+
+    fn set_position(&mut self, position: Vector2f) {
+        self.rect.set_position(position);
+    }
+
+    fn draw(&self, target: &mut dyn RenderTarget, font: &Font) {
+        self.rect.draw(target);
+        let mut text = Text::new(self.shown_label(), font, TEXT_SIZE);
+        let bounds = text.local_bounds();
+        let (top_left, _) = self.rect.get_bounds();
+        text.set_position((
+            (top_left.x + (self.rect.dimensions.x - bounds.width) / 2.0 - bounds.left).round(),
+            (top_left.y + (self.rect.dimensions.y - bounds.height) / 2.0 - bounds.top).round(),
+        ));
+        target.draw(&text);
+    }
+
+    fn widget_type(&self) -> WidgetKind {
+        WidgetKind::Button
+    }
+
+    fn handle_event(&mut self, event: &WidgetEvent) -> EventResponse {
+        let response = match *event {
+            WidgetEvent::MouseEnter => {
+                self.hovered = true;
+                EventResponse::consumed()
+            }
+            WidgetEvent::MouseLeave => {
+                self.hovered = false;
+                EventResponse::consumed()
+            }
+            WidgetEvent::MouseDown { .. } => {
+                self.pressed = true;
+                EventResponse::consumed()
+            }
+            WidgetEvent::MouseUp { x, y } if self.pressed => {
+                self.pressed = false;
+                //letting go somewhere else cancels the click
+                if self.contains(x, y) {
+                    self.clicked()
+                } else {
+                    EventResponse::consumed()
+                }
+            }
+            WidgetEvent::MouseMove { x, y } if self.pressed => {
+                self.hovered = self.contains(x, y);
+                EventResponse::consumed()
+            }
+            _ => EventResponse::Ignored,
+        };
+        self.update_color();
+        response
+    }
+
+    fn debug_string(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn tooltip(&self) -> Option<String> {
+        Some(self.command.description().to_string())
+    }
+
+    fn sync(&mut self, example: &ExamplePlanet, space: &WorldSpace) {
+        if let Some((flag, _)) = self.alt_label {
+            self.showing_alt = flag.get(example, space);
+        }
+        if self.armed_at.is_some_and(|t| t.elapsed() > CONFIRM_TIME) {
+            self.armed_at = None;
+            self.update_color();
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use sfml::{
+    graphics::{Color, Font, RectangleShape, RenderTarget, Shape, Text, Transformable},
+    system::Vector2f,
+};
+
+use crate::{
+    bodies::WorldSpace,
+    gui::ExamplePlanet,
+    widgets::{EventResponse, Flag, Widget, WidgetAction, WidgetEvent, WidgetKind},
+};
+const TEXT_SIZE: u32 = 20;
+const LABEL_GAP: f32 = 10.0;
+const CHECK_INSET: f32 = 5.0;
+//a box that shows whether `flag` is on, with its name next to it
+#[derive(Debug)]
+pub struct Checkbox {
+    flag: Flag,
+    checked: bool,
+    hovered: bool,
+    pressed: bool,
+    position: Vector2f,
+    dimensions: Vector2f,
+    layer: usize,
+}
+impl Checkbox {
+    pub fn new<T: Into<Vector2f>>(flag: Flag, layer: usize, dims: T) -> Checkbox {
+        Checkbox {
+            flag,
+            checked: false,
+            hovered: false,
+            pressed: false,
+            position: Vector2f::new(0.0, 0.0),
+            dimensions: dims.into(),
+            layer,
+        }
+    }
+    fn contains(&self, x: i32, y: i32) -> bool {
+        (x as f32) >= self.position.x
+            && (x as f32) <= self.position.x + self.dimensions.x
+            && (y as f32) >= self.position.y
+            && (y as f32) <= self.position.y + self.dimensions.y
+    }
+}
+impl Widget for Checkbox {
+    fn get_bounds(&self) -> (Vector2f, Vector2f) {
+        (self.position, self.position + self.dimensions)
+    }
+
+    fn get_layer(&self) -> usize {
+        self.layer
+    }
+
+    fn size(&self) -> Vector2f {
+        self.dimensions
+    }
+
+    fn set_position(&mut self, position: Vector2f) {
+        self.position = position;
+    }
+
+    fn draw(&self, target: &mut dyn RenderTarget, font: &Font) {
+        //the box is a square as tall as the widget, and the label fills the rest
+        let side = self.dimensions.y;
+        let mut outline = RectangleShape::with_size(Vector2f::new(side, side));
+        outline.set_position(self.position);
+        outline.set_fill_color(if self.pressed {
+            Color::rgb(20, 20, 20)
+        } else {
+            Color::rgb(40, 40, 40)
+        });
+        outline.set_outline_thickness(2.0);
+        outline.set_outline_color(if self.hovered {
+            Color::WHITE
+        } else {
+            Color::rgb(100, 100, 100)
+        });
+        target.draw(&outline);
+        if self.checked {
+            let inner = side - 2.0 * CHECK_INSET;
+            let mut check = RectangleShape::with_size(Vector2f::new(inner, inner));
+            check.set_position(self.position + Vector2f::new(CHECK_INSET, CHECK_INSET));
+            check.set_fill_color(Color::rgb(120, 200, 120));
+            target.draw(&check);
+        }
+        let mut text = Text::new(self.flag.name(), font, TEXT_SIZE);
+        text.set_position((
+            self.position.x + side + LABEL_GAP,
+            self.position.y + (side - TEXT_SIZE as f32) / 2.0 - 2.0,
+        ));
+        target.draw(&text);
+    }
+
+    fn widget_type(&self) -> WidgetKind {
+        WidgetKind::Checkbox
+    }
+
+    fn handle_event(&mut self, event: &WidgetEvent) -> EventResponse {
+        match *event {
+            WidgetEvent::MouseEnter => {
+                self.hovered = true;
+                EventResponse::consumed()
+            }
+            WidgetEvent::MouseLeave => {
+                self.hovered = false;
+                EventResponse::consumed()
+            }
+            WidgetEvent::MouseDown { .. } => {
+                self.pressed = true;
+                EventResponse::consumed()
+            }
+            WidgetEvent::MouseUp { x, y } if self.pressed => {
+                self.pressed = false;
+                if self.contains(x, y) {
+                    EventResponse::Consumed(vec![WidgetAction::SetFlag(self.flag, !self.checked)])
+                } else {
+                    EventResponse::consumed()
+                }
+            }
+            WidgetEvent::MouseMove { x, y } if self.pressed => {
+                self.hovered = self.contains(x, y);
+                EventResponse::consumed()
+            }
+            _ => EventResponse::Ignored,
+        }
+    }
+
+    fn debug_string(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn tooltip(&self) -> Option<String> {
+        Some(self.flag.description().to_string())
+    }
+
+    fn sync(&mut self, example: &ExamplePlanet, space: &WorldSpace) {
+        self.checked = self.flag.get(example, space);
+    }
+}
@@ -0,0 +1,491 @@
+use sfml::{
+    graphics::{
+        CircleShape, Color, Font, PrimitiveType, RectangleShape, RenderTarget, Shape, Text,
+        Transformable, Vertex, VertexArray,
+    },
+    system::Vector2f,
+    window::Key,
+};
+
+use crate::{
+    bodies::WorldSpace,
+    gui::ExamplePlanet,
+    widgets::{EventResponse, Widget, WidgetAction, WidgetEvent, WidgetKind},
+};
+const SQUARE_SIZE: f32 = 200.0;
+const BAR_WIDTH: f32 = 24.0;
+const GAP: f32 = 10.0;
+const ROW_HEIGHT: f32 = 28.0;
+const SWATCH_SIZE: f32 = 24.0;
+const HEX_BOX_WIDTH: f32 = 130.0;
+const TEXT_SIZE: u32 = 20;
+const MAX_RECENT: usize = 8;
+const WIDTH: f32 = SQUARE_SIZE + GAP + BAR_WIDTH;
+//colours as hue in degrees, then saturation, value and alpha from 0 to 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsva {
+    pub h: f32,
+    pub s: f32,
+    pub v: f32,
+    pub a: f32,
+}
+impl Hsva {
+    pub fn to_color(self) -> Color {
+        let c = self.v * self.s;
+        let h = (self.h.rem_euclid(360.0)) / 60.0;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        let m = self.v - c;
+        let channel = |f: f32| ((f + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+        Color::rgba(
+            channel(r),
+            channel(g),
+            channel(b),
+            (self.a * 255.0).round().clamp(0.0, 255.0) as u8,
+        )
+    }
+    //greys have no hue, so `hue` is kept for them instead of jumping back to red
+    pub fn from_color(color: Color, hue: f32) -> Hsva {
+        let r = color.r as f32 / 255.0;
+        let g = color.g as f32 / 255.0;
+        let b = color.b as f32 / 255.0;
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+        let h = if delta == 0.0 {
+            hue
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        Hsva {
+            h,
+            s: if max == 0.0 { 0.0 } else { delta / max },
+            v: max,
+            a: color.a as f32 / 255.0,
+        }
+    }
+}
+//"#rrggbb" or "#rrggbbaa", the # is optional
+pub fn parse_hex(hex: &str) -> Option<Color> {
+    let digits = hex.trim().trim_start_matches('#');
+    if !(digits.len() == 6 || digits.len() == 8) || !digits.is_ascii() {
+        return None;
+    }
+    let byte = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).ok();
+    let alpha = if digits.len() == 8 { byte(6)? } else { 255 };
+    Some(Color::rgba(byte(0)?, byte(2)?, byte(4)?, alpha))
+}
+pub fn to_hex(color: Color) -> String {
+    format!(
+        "#{:02x}{:02x}{:02x}{:02x}",
+        color.r, color.g, color.b, color.a
+    )
+}
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Part {
+    SaturationValue,
+    Hue,
+    Alpha,
+}
+//picks the spawn colour with a saturation/value square, a hue bar and an alpha bar,
+//and remembers the last few colours that were picked
+#[derive(Debug)]
+pub struct ColorPicker {
+    hsva: Hsva,
+    //what the colour was last set to, so changes made elsewhere can be noticed
+    shown: Color,
+    recent: Vec<Color>,
+    dragging: Option<Part>,
+    editing: bool,
+    buffer: String,
+    rejected: bool,
+    position: Vector2f,
+    layer: usize,
+}
+impl ColorPicker {
+    pub fn new(layer: usize) -> ColorPicker {
+        ColorPicker {
+            hsva: Hsva::from_color(Color::WHITE, 0.0),
+            shown: Color::WHITE,
+            recent: Vec::new(),
+            dragging: None,
+            editing: false,
+            buffer: String::new(),
+            rejected: false,
+            position: Vector2f::new(0.0, 0.0),
+            layer,
+        }
+    }
+    fn square_origin(&self) -> Vector2f {
+        self.position
+    }
+    fn hue_origin(&self) -> Vector2f {
+        self.position + Vector2f::new(SQUARE_SIZE + GAP, 0.0)
+    }
+    fn alpha_origin(&self) -> Vector2f {
+        self.position + Vector2f::new(0.0, SQUARE_SIZE + GAP)
+    }
+    fn entry_origin(&self) -> Vector2f {
+        self.alpha_origin() + Vector2f::new(0.0, BAR_WIDTH + GAP)
+    }
+    fn hex_origin(&self) -> Vector2f {
+        self.entry_origin() + Vector2f::new(WIDTH - HEX_BOX_WIDTH, 0.0)
+    }
+    fn palette_origin(&self) -> Vector2f {
+        self.entry_origin() + Vector2f::new(0.0, ROW_HEIGHT + GAP)
+    }
+    fn part_at(&self, point: Vector2f) -> Option<Part> {
+        if within(
+            point,
+            self.square_origin(),
+            Vector2f::new(SQUARE_SIZE, SQUARE_SIZE),
+        ) {
+            Some(Part::SaturationValue)
+        } else if within(
+            point,
+            self.hue_origin(),
+            Vector2f::new(BAR_WIDTH, SQUARE_SIZE),
+        ) {
+            Some(Part::Hue)
+        } else if within(point, self.alpha_origin(), Vector2f::new(WIDTH, BAR_WIDTH)) {
+            Some(Part::Alpha)
+        } else {
+            None
+        }
+    }
+    fn swatch_at(&self, point: Vector2f) -> Option<Color> {
+        self.recent.iter().enumerate().find_map(|(i, color)| {
+            let offset = Vector2f::new(i as f32 * (SWATCH_SIZE + 5.0), 0.0);
+            let origin = self.palette_origin() + offset;
+            if within(point, origin, Vector2f::new(SWATCH_SIZE, SWATCH_SIZE)) {
+                Some(*color)
+            } else {
+                None
+            }
+        })
+    }
+    fn drag(&mut self, part: Part, point: Vector2f) -> EventResponse {
+        let fraction =
+            |value: f32, start: f32, length: f32| ((value - start) / length).clamp(0.0, 1.0);
+        match part {
+            Part::SaturationValue => {
+                let origin = self.square_origin();
+                self.hsva.s = fraction(point.x, origin.x, SQUARE_SIZE);
+                self.hsva.v = 1.0 - fraction(point.y, origin.y, SQUARE_SIZE);
+            }
+            Part::Hue => {
+                self.hsva.h = 360.0 * fraction(point.y, self.hue_origin().y, SQUARE_SIZE);
+            }
+            Part::Alpha => {
+                self.hsva.a = fraction(point.x, self.alpha_origin().x, WIDTH);
+            }
+        }
+        self.set(self.hsva.to_color())
+    }
+    fn set(&mut self, color: Color) -> EventResponse {
+        self.shown = color;
+        EventResponse::Consumed(vec![WidgetAction::SetColor(color)])
+    }
+    fn remember(&mut self, color: Color) {
+        self.recent.retain(|c| *c != color);
+        self.recent.insert(0, color);
+        self.recent.truncate(MAX_RECENT);
+    }
+    fn submit(&mut self) -> EventResponse {
+        match parse_hex(&self.buffer) {
+            Some(color) => {
+                self.hsva = Hsva::from_color(color, self.hsva.h);
+                self.remember(color);
+                let mut response = self.set(color);
+                if let EventResponse::Consumed(actions) = &mut response {
+                    actions.push(WidgetAction::ReleaseFocus);
+                }
+                response
+            }
+            None => {
+                self.rejected = true;
+                EventResponse::consumed()
+            }
+        }
+    }
+    fn draw_gradients(&self, target: &mut dyn RenderTarget) {
+        let mut quads = VertexArray::new(PrimitiveType::Quads, 0);
+        let mut quad = |origin: Vector2f, size: Vector2f, colors: [Color; 4]| {
+            let corners = [
+                origin,
+                origin + Vector2f::new(size.x, 0.0),
+                origin + size,
+                origin + Vector2f::new(0.0, size.y),
+            ];
+            for (corner, color) in corners.iter().zip(colors.iter()) {
+                quads.append(&Vertex::with_pos_color(*corner, *color));
+            }
+        };
+        let pure = Hsva {
+            h: self.hsva.h,
+            s: 1.0,
+            v: 1.0,
+            a: 1.0,
+        }
+        .to_color();
+        //white to the hue across, then darkened towards black going down
+        let square = Vector2f::new(SQUARE_SIZE, SQUARE_SIZE);
+        quad(
+            self.square_origin(),
+            square,
+            [Color::WHITE, pure, pure, Color::WHITE],
+        );
+        quad(
+            self.square_origin(),
+            square,
+            [
+                Color::TRANSPARENT,
+                Color::TRANSPARENT,
+                Color::BLACK,
+                Color::BLACK,
+            ],
+        );
+        for i in 0..6 {
+            let segment = SQUARE_SIZE / 6.0;
+            let hue = |j: u32| {
+                Hsva {
+                    h: 60.0 * j as f32,
+                    s: 1.0,
+                    v: 1.0,
+                    a: 1.0,
+                }
+                .to_color()
+            };
+            quad(
+                self.hue_origin() + Vector2f::new(0.0, i as f32 * segment),
+                Vector2f::new(BAR_WIDTH, segment),
+                [hue(i), hue(i), hue(i + 1), hue(i + 1)],
+            );
+        }
+        let opaque = Hsva {
+            a: 1.0,
+            ..self.hsva
+        }
+        .to_color();
+        let clear = Hsva {
+            a: 0.0,
+            ..self.hsva
+        }
+        .to_color();
+        quad(
+            self.alpha_origin(),
+            Vector2f::new(WIDTH, BAR_WIDTH),
+            [clear, opaque, opaque, clear],
+        );
+        let mut behind_alpha = RectangleShape::with_size(Vector2f::new(WIDTH, BAR_WIDTH));
+        behind_alpha.set_position(self.alpha_origin());
+        behind_alpha.set_fill_color(Color::rgb(90, 90, 90));
+        target.draw(&behind_alpha);
+        target.draw(&quads);
+    }
+    fn draw_markers(&self, target: &mut dyn RenderTarget) {
+        let mut ring = CircleShape::new(5.0, 16);
+        ring.set_fill_color(Color::TRANSPARENT);
+        ring.set_outline_thickness(2.0);
+        ring.set_outline_color(if self.hsva.v > 0.5 {
+            Color::BLACK
+        } else {
+            Color::WHITE
+        });
+        ring.set_position(
+            self.square_origin()
+                + Vector2f::new(
+                    self.hsva.s * SQUARE_SIZE - 5.0,
+                    (1.0 - self.hsva.v) * SQUARE_SIZE - 5.0,
+                ),
+        );
+        target.draw(&ring);
+        let mut hue_marker = RectangleShape::with_size(Vector2f::new(BAR_WIDTH + 4.0, 3.0));
+        hue_marker.set_fill_color(Color::WHITE);
+        hue_marker.set_position(
+            self.hue_origin() + Vector2f::new(-2.0, self.hsva.h / 360.0 * SQUARE_SIZE - 1.5),
+        );
+        target.draw(&hue_marker);
+        let mut alpha_marker = RectangleShape::with_size(Vector2f::new(3.0, BAR_WIDTH + 4.0));
+        alpha_marker.set_fill_color(Color::WHITE);
+        alpha_marker
+            .set_position(self.alpha_origin() + Vector2f::new(self.hsva.a * WIDTH - 1.5, -2.0));
+        target.draw(&alpha_marker);
+    }
+}
+fn within(point: Vector2f, origin: Vector2f, size: Vector2f) -> bool {
+    point.x >= origin.x
+        && point.x <= origin.x + size.x
+        && point.y >= origin.y
+        && point.y <= origin.y + size.y
+}
+impl Widget for ColorPicker {
+    fn get_bounds(&self) -> (Vector2f, Vector2f) {
+        (self.position, self.position + self.size())
+    }
+
+    fn get_layer(&self) -> usize {
+        self.layer
+    }
+
+    fn size(&self) -> Vector2f {
+        Vector2f::new(
+            WIDTH,
+            SQUARE_SIZE + GAP + BAR_WIDTH + GAP + ROW_HEIGHT + GAP + SWATCH_SIZE,
+        )
+    }
+
+    fn set_position(&mut self, position: Vector2f) {
+        self.position = position;
+    }
+
+    fn draw(&self, target: &mut dyn RenderTarget, font: &Font) {
+        self.draw_gradients(target);
+        self.draw_markers(target);
+        let mut preview =
+            RectangleShape::with_size(Vector2f::new(WIDTH - HEX_BOX_WIDTH - GAP, ROW_HEIGHT));
+        preview.set_position(self.entry_origin());
+        preview.set_fill_color(self.shown);
+        preview.set_outline_thickness(1.0);
+        preview.set_outline_color(Color::rgb(100, 100, 100));
+        target.draw(&preview);
+        let mut hex_box = RectangleShape::with_size(Vector2f::new(HEX_BOX_WIDTH, ROW_HEIGHT));
+        hex_box.set_position(self.hex_origin());
+        hex_box.set_fill_color(Color::rgb(40, 40, 40));
+        hex_box.set_outline_thickness(2.0);
+        hex_box.set_outline_color(if self.rejected {
+            Color::RED
+        } else if self.editing {
+            Color::WHITE
+        } else {
+            Color::rgb(100, 100, 100)
+        });
+        target.draw(&hex_box);
+        let shown = if self.editing {
+            format!("{}_", self.buffer)
+        } else {
+            to_hex(self.shown)
+        };
+        let mut text = Text::new(&shown, font, TEXT_SIZE);
+        text.set_position(self.hex_origin() + Vector2f::new(4.0, 2.0));
+        target.draw(&text);
+        for (i, color) in self.recent.iter().enumerate() {
+            let mut swatch = RectangleShape::with_size(Vector2f::new(SWATCH_SIZE, SWATCH_SIZE));
+            swatch.set_position(
+                self.palette_origin() + Vector2f::new(i as f32 * (SWATCH_SIZE + 5.0), 0.0),
+            );
+            swatch.set_fill_color(*color);
+            swatch.set_outline_thickness(1.0);
+            swatch.set_outline_color(Color::rgb(100, 100, 100));
+            target.draw(&swatch);
+        }
+    }
+
+    fn widget_type(&self) -> WidgetKind {
+        WidgetKind::ColorPicker
+    }
+
+    fn handle_event(&mut self, event: &WidgetEvent) -> EventResponse {
+        match *event {
+            WidgetEvent::MouseDown { x, y } => {
+                let point = Vector2f::new(x as f32, y as f32);
+                //only the hex box keeps the keyboard, clicking anything else hands it back
+                if within(
+                    point,
+                    self.hex_origin(),
+                    Vector2f::new(HEX_BOX_WIDTH, ROW_HEIGHT),
+                ) {
+                    return EventResponse::consumed();
+                }
+                let mut response = if let Some(part) = self.part_at(point) {
+                    self.dragging = Some(part);
+                    self.drag(part, point)
+                } else if let Some(color) = self.swatch_at(point) {
+                    self.hsva = Hsva::from_color(color, self.hsva.h);
+                    self.set(color)
+                } else {
+                    EventResponse::consumed()
+                };
+                if let EventResponse::Consumed(actions) = &mut response {
+                    actions.push(WidgetAction::ReleaseFocus);
+                }
+                response
+            }
+            WidgetEvent::MouseMove { x, y } => match self.dragging {
+                Some(part) => self.drag(part, Vector2f::new(x as f32, y as f32)),
+                None => EventResponse::Ignored,
+            },
+            WidgetEvent::MouseUp { .. } => {
+                if self.dragging.take().is_some() {
+                    self.remember(self.shown);
+                }
+                EventResponse::consumed()
+            }
+            WidgetEvent::FocusGained => {
+                self.editing = true;
+                self.buffer.clear();
+                EventResponse::consumed()
+            }
+            WidgetEvent::FocusLost => {
+                self.editing = false;
+                self.rejected = false;
+                EventResponse::consumed()
+            }
+            WidgetEvent::Text(c) if self.editing => {
+                if (c.is_ascii_hexdigit() || (c == '#' && self.buffer.is_empty()))
+                    && self.buffer.len() < 9
+                {
+                    self.buffer.push(c);
+                    self.rejected = false;
+                }
+                EventResponse::consumed()
+            }
+            WidgetEvent::Key { code, .. } if self.editing => match code {
+                Key::Return => self.submit(),
+                Key::Escape => EventResponse::Consumed(vec![WidgetAction::ReleaseFocus]),
+                Key::BackSpace => {
+                    self.buffer.pop();
+                    self.rejected = false;
+                    EventResponse::consumed()
+                }
+                _ => EventResponse::consumed(),
+            },
+            _ => EventResponse::Ignored,
+        }
+    }
+
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+
+    fn debug_string(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn tooltip(&self) -> Option<String> {
+        Some(
+            "Drag to pick the colour of new bodies, type a hex code like #ff8800 into the box, \
+             or click a recent colour"
+                .to_string(),
+        )
+    }
+
+    fn sync(&mut self, example: &ExamplePlanet, _space: &WorldSpace) {
+        let color = example.shape.fill_color();
+        if color != self.shown {
+            self.shown = color;
+            self.hsva = Hsva::from_color(color, self.hsva.h);
+        }
+    }
+}
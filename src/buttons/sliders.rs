@@ -1,6 +1,6 @@
 use sfml::{
     graphics::{
-        CircleShape, Color, Drawable, PrimitiveType, RenderTarget, Shape, Transformable, Vertex,
+        Color, Drawable, Font, PrimitiveType, RenderTarget, Text, Transformable, Vertex,
         VertexArray,
     },
     system::Vector2f,
@@ -8,153 +8,174 @@ use sfml::{
 
 use crate::{
     bodies::WorldSpace,
-    gui::Gui,
+    gui::ExamplePlanet,
     shapes::RoundedRect,
-    widgets::{Widget, WidgetKind},
+    widgets::{Binding, EventResponse, Widget, WidgetAction, WidgetEvent, WidgetKind},
 };
+const LABEL_HEIGHT: f32 = 28.0;
+//how far along the slider one notch of the mouse wheel moves the handle
+const WHEEL_FRACTION: f32 = 0.02;
 #[allow(unused)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ColorType {
     Red,
     Green,
     Blue,
     Alpha,
 }
+#[derive(Debug, Clone, Copy)]
+pub struct SliderRange {
+    pub min: f32,
+    pub max: f32,
+    pub initial: f32,
+    pub step: f32,
+    pub log_scale: bool,
+}
+impl SliderRange {
+    pub fn linear(min: f32, max: f32, initial: f32) -> Self {
+        assert!(max > min, "A slider needs min < max, got {}..{}", min, max);
+        SliderRange {
+            min,
+            max,
+            initial,
+            step: 0.0,
+            log_scale: false,
+        }
+    }
+    pub fn log(min: f32, max: f32, initial: f32) -> Self {
+        assert!(
+            min > 0.0 && max > min,
+            "A logarithmic slider needs 0 < min < max, got {}..{}",
+            min,
+            max
+        );
+        SliderRange {
+            min,
+            max,
+            initial,
+            step: 0.0,
+            log_scale: true,
+        }
+    }
+    //values are rounded to the nearest multiple of `step` above `min`, 0 means no rounding
+    pub fn with_step(mut self, step: f32) -> Self {
+        assert!(step >= 0.0, "A slider step can't be negative, got {}", step);
+        self.step = step;
+        self
+    }
+    fn snap(&self, value: f32) -> f32 {
+        if self.step > 0.0 {
+            (self.min + ((value - self.min) / self.step).round() * self.step)
+                .clamp(self.min, self.max)
+        } else {
+            value
+        }
+    }
+    //maps a fraction of the slider width (0 to 1) to a value
+    fn value_at(&self, fraction: f32) -> f32 {
+        let fraction = fraction.clamp(0.0, 1.0);
+        let value = if self.log_scale {
+            self.min * (self.max / self.min).powf(fraction)
+        } else {
+            self.min + (self.max - self.min) * fraction
+        };
+        self.snap(value)
+    }
+    fn fraction_of(&self, value: f32) -> f32 {
+        let fraction = if self.log_scale {
+            (value / self.min).ln() / (self.max / self.min).ln()
+        } else {
+            (value - self.min) / (self.max - self.min)
+        };
+        if fraction.is_nan() {
+            0.0
+        } else {
+            fraction.clamp(0.0, 1.0)
+        }
+    }
+}
 #[derive(Debug)]
 pub struct Slider<'a> {
     pub handle: RoundedRect<'a>,
     pub array: VertexArray,
     pub position: Vector2f,
-    pub value: u8,
+    pub value: f32,
     pub max_width: f32,
     layer: usize,
     clicked: bool,
-    color_type: ColorType,
+    binding: Binding,
+    range: SliderRange,
 }
 
 impl<'a> Slider<'a> {
+    #[allow(unused)]
     pub fn new<T: Into<Vector2f>>(
         color_type: ColorType,
         layer: usize,
         pos: T,
         dims: T,
         radii: f32,
+    ) -> Slider<'a> {
+        Slider::bound(
+            Binding::Color(color_type),
+            SliderRange::linear(0.0, 255.0, 255.0).with_step(1.0),
+            layer,
+            pos,
+            dims,
+            radii,
+        )
+    }
+    pub fn bound<T: Into<Vector2f>>(
+        binding: Binding,
+        range: SliderRange,
+        layer: usize,
+        pos: T,
+        dims: T,
+        radii: f32,
     ) -> Slider<'a> {
         let bpos: Vector2f = pos.into();
         let bdims: Vector2f = dims.into();
-        let mut array = VertexArray::new(PrimitiveType::TriangleStrip, 6);
-        let vertices;
+        let mut array = VertexArray::new(PrimitiveType::TriangleStrip, 0);
         let max_width = bdims.x;
-        match color_type {
-            ColorType::Red => {
-                vertices = [
-                    Vertex::new(bpos, Color::RED, Default::default()),
-                    Vertex::new((bpos.x, bpos.y + bdims.y), Color::RED, Default::default()),
-                    Vertex::new(
-                        (bpos.x + max_width / 2.0, bpos.y),
-                        Color::rgb(255, 127, 127),
-                        Default::default(),
-                    ),
-                    Vertex::new(
-                        (bpos.x + max_width / 2.0, bpos.y + bdims.y),
-                        Color::rgb(255, 127, 127),
-                        Default::default(),
-                    ),
-                    Vertex::new(
-                        (bpos.x + max_width, bpos.y),
-                        Color::WHITE,
-                        Default::default(),
-                    ),
-                    Vertex::new(
-                        (bpos.x + max_width, bpos.y + bdims.y),
-                        Color::WHITE,
-                        Default::default(),
-                    ),
-                ]
-            }
-            ColorType::Green => {
-                vertices = [
-                    Vertex::new(bpos, Color::GREEN, Default::default()),
-                    Vertex::new((bpos.x, bpos.y + bdims.y), Color::GREEN, Default::default()),
-                    Vertex::new(
-                        (bpos.x + max_width / 2.0, bpos.y),
-                        Color::rgb(127, 255, 127),
-                        Default::default(),
-                    ),
-                    Vertex::new(
-                        (bpos.x + max_width / 2.0, bpos.y + bdims.y),
-                        Color::rgb(127, 255, 127),
-                        Default::default(),
-                    ),
-                    Vertex::new(
-                        (bpos.x + max_width, bpos.y),
-                        Color::WHITE,
-                        Default::default(),
-                    ),
-                    Vertex::new(
-                        (bpos.x + max_width, bpos.y + bdims.y),
-                        Color::WHITE,
-                        Default::default(),
-                    ),
-                ]
-            }
-            ColorType::Blue => {
-                vertices = [
-                    Vertex::new(bpos, Color::BLUE, Default::default()),
-                    Vertex::new((bpos.x, bpos.y + bdims.y), Color::BLUE, Default::default()),
-                    Vertex::new(
-                        (bpos.x + max_width / 2.0, bpos.y),
-                        Color::rgb(127, 127, 255),
-                        Default::default(),
-                    ),
-                    Vertex::new(
-                        (bpos.x + max_width / 2.0, bpos.y + bdims.y),
-                        Color::rgb(127, 127, 255),
-                        Default::default(),
-                    ),
-                    Vertex::new(
-                        (bpos.x + max_width, bpos.y),
-                        Color::WHITE,
-                        Default::default(),
-                    ),
-                    Vertex::new(
-                        (bpos.x + max_width, bpos.y + bdims.y),
-                        Color::WHITE,
-                        Default::default(),
-                    ),
-                ]
-            }
-            ColorType::Alpha => {
-                vertices = [
-                    Vertex::new(bpos, Color::WHITE, Default::default()),
-                    Vertex::new((bpos.x, bpos.y + bdims.y), Color::WHITE, Default::default()),
-                    Vertex::new(
-                        (bpos.x + max_width / 2.0, bpos.y),
-                        Color::rgba(255, 255, 255, 127),
-                        Default::default(),
-                    ),
-                    Vertex::new(
-                        (bpos.x + max_width / 2.0, bpos.y + bdims.y),
-                        Color::rgba(255, 255, 255, 127),
-                        Default::default(),
-                    ),
-                    Vertex::new(
-                        (bpos.x + max_width, bpos.y),
-                        Color::rgba(255, 255, 255, 0),
-                        Default::default(),
-                    ),
-                    Vertex::new(
-                        (bpos.x + max_width, bpos.y + bdims.y),
-                        Color::rgba(255, 255, 255, 0),
-                        Default::default(),
-                    ),
-                ]
-            }
-        }
+        let (start, middle, end) = match binding {
+            Binding::Color(ColorType::Red) => (Color::BLACK, Color::rgb(127, 0, 0), Color::RED),
+            Binding::Color(ColorType::Green) => (Color::BLACK, Color::rgb(0, 127, 0), Color::GREEN),
+            Binding::Color(ColorType::Blue) => (Color::BLACK, Color::rgb(0, 0, 127), Color::BLUE),
+            Binding::Color(ColorType::Alpha) => (
+                Color::rgba(255, 255, 255, 0),
+                Color::rgba(255, 255, 255, 127),
+                Color::WHITE,
+            ),
+            _ => (
+                Color::rgb(60, 60, 60),
+                Color::rgb(127, 127, 127),
+                Color::rgb(220, 220, 220),
+            ),
+        };
+        let vertices = [
+            Vertex::new(bpos, start, Default::default()),
+            Vertex::new((bpos.x, bpos.y + bdims.y), start, Default::default()),
+            Vertex::new(
+                (bpos.x + max_width / 2.0, bpos.y),
+                middle,
+                Default::default(),
+            ),
+            Vertex::new(
+                (bpos.x + max_width / 2.0, bpos.y + bdims.y),
+                middle,
+                Default::default(),
+            ),
+            Vertex::new((bpos.x + max_width, bpos.y), end, Default::default()),
+            Vertex::new(
+                (bpos.x + max_width, bpos.y + bdims.y),
+                end,
+                Default::default(),
+            ),
+        ];
         for a in vertices.iter() {
             array.append(a);
         }
-        Slider {
+        let mut slider = Slider {
             handle: RoundedRect::new(
                 radii,
                 (15.0, bdims.y + 3.0),
@@ -163,80 +184,125 @@ impl<'a> Slider<'a> {
             ),
             array,
             position: bpos,
-            value: 255,
+            value: range.initial,
             max_width: bdims.x,
             layer,
             clicked: false,
-            color_type,
-        }
+            binding,
+            range,
+        };
+        slider.set_value(range.initial);
+        slider
+    }
+    fn drag_to(&mut self, x: i32) -> EventResponse {
+        let fraction = (x as f32 - self.position.x) / self.max_width;
+        let value = self.range.value_at(fraction);
+        self.set_value(value);
+        EventResponse::Consumed(vec![WidgetAction::Set(self.binding, value)])
+    }
+    //moves the handle to where `value` sits on the slider, without applying it to anything
+    pub fn set_value(&mut self, value: f32) {
+        let hhw = self.handle.dimensions.x / 2.0; //half handle width
+        let fraction = self.range.fraction_of(value);
+        self.value = value;
+        self.handle.set_position((
+            self.position.x + fraction * self.max_width - hhw,
+            self.position.y,
+        ));
     }
 }
 impl<'a> Widget for Slider<'a> {
     fn get_bounds(&self) -> (Vector2f, Vector2f) {
-        self.handle.get_bounds()
+        let (handle_start, handle_end) = self.handle.get_bounds();
+        let track_start = self.position;
+        let track_end = self.position + Vector2f::new(self.max_width, self.handle.dimensions.y);
+        (
+            Vector2f::new(
+                handle_start.x.min(track_start.x),
+                handle_start.y.min(track_start.y),
+            ),
+            Vector2f::new(handle_end.x.max(track_end.x), handle_end.y.max(track_end.y)),
+        )
     }
 
     fn get_layer(&self) -> usize {
         self.layer
     }
 
-    fn draw(&self, target: &mut dyn RenderTarget) {
+    fn size(&self) -> Vector2f {
+        Vector2f::new(self.max_width, LABEL_HEIGHT + self.handle.dimensions.y)
+    }
+
+    fn set_position(&mut self, position: Vector2f) {
+        let track = position + Vector2f::new(0.0, LABEL_HEIGHT);
+        let offset = track - self.position;
+        for i in 0..self.array.vertex_count() {
+            self.array[i].position += offset;
+        }
+        self.position = track;
+        self.set_value(self.value);
+    }
+
+    fn draw(&self, target: &mut dyn RenderTarget, font: &Font) {
         self.array.draw(target, Default::default());
         self.handle.draw(target);
+        let mut label = Text::new(
+            &format!(
+                "{}: {}",
+                self.binding.name(),
+                self.binding.format(self.value)
+            ),
+            font,
+            20,
+        );
+        label.set_position((self.position.x, self.position.y - LABEL_HEIGHT));
+        target.draw(&label);
     }
 
     fn widget_type(&self) -> crate::widgets::WidgetKind {
         WidgetKind::Slider
     }
 
-    fn click(&mut self, _: &Gui, _: &mut WorldSpace) {
-        self.clicked = true;
+    fn handle_event(&mut self, event: &WidgetEvent) -> EventResponse {
+        match *event {
+            WidgetEvent::MouseDown { x, .. } => {
+                self.clicked = true;
+                self.drag_to(x)
+            }
+            WidgetEvent::MouseMove { x, .. } if self.clicked => self.drag_to(x),
+            WidgetEvent::MouseUp { .. } if self.clicked => {
+                self.clicked = false;
+                EventResponse::consumed()
+            }
+            WidgetEvent::Wheel { delta, .. } => {
+                let fraction = self.range.fraction_of(self.value) + delta * WHEEL_FRACTION;
+                let mut value = self.range.value_at(fraction);
+                if value == self.value && self.range.step > 0.0 {
+                    value = (self.value + delta.signum() * self.range.step)
+                        .clamp(self.range.min, self.range.max);
+                }
+                self.set_value(value);
+                EventResponse::Consumed(vec![WidgetAction::Set(self.binding, value)])
+            }
+            _ => EventResponse::Ignored,
+        }
     }
 
-    fn release_click(&mut self, _: &mut CircleShape, _: &mut WorldSpace) {
-        self.clicked = false;
+    fn debug_string(&self) -> String {
+        format!("{:?}", self)
     }
 
-    fn is_click_held(&self) -> bool {
-        self.clicked
+    fn tooltip(&self) -> Option<String> {
+        Some(self.binding.description().to_string())
     }
 
-    fn debug_string(&self) -> String {
-        format!("{:?}", self)
+    fn binding(&self) -> Option<Binding> {
+        Some(self.binding)
     }
 
-    fn mouse_moved(&mut self, planet: &mut CircleShape, x: i32, _: i32) {
-        let hhw = self.handle.dimensions.x / 2.0; //half handle width
-        if x as f32 > self.position.x + self.max_width {
-            self.handle
-                .set_position((self.position.x - hhw + self.max_width, self.position.y));
-            self.value = u8::MAX;
-        } else if (x as f32) < self.position.x {
-            self.handle
-                .set_position((self.position.x - hhw, self.position.y));
-            self.value = u8::MIN;
-        } else {
-            self.handle.set_position((x as f32, self.position.y));
-            self.value = (self.handle.position().x - self.position.x / self.max_width) as u8;
-        }
-        let mut color = planet.fill_color();
-        match self.color_type {
-            ColorType::Red => {
-                color.g = self.value;
-                color.b = self.value;
-            }
-            ColorType::Green => {
-                color.r = self.value;
-                color.b = self.value;
-            }
-            ColorType::Blue => {
-                color.g = self.value;
-                color.r = self.value;
-            }
-            ColorType::Alpha => {
-                color.a = self.value;
-            }
+    fn sync(&mut self, example: &ExamplePlanet, space: &WorldSpace) {
+        if !self.clicked {
+            self.set_value(self.binding.get(example, space));
         }
-        planet.set_fill_color(color);
     }
 }
@@ -0,0 +1,97 @@
+use sfml::{
+    graphics::{Font, RenderTarget, Text, Transformable},
+    system::Vector2f,
+};
+
+use crate::{
+    bodies::WorldSpace,
+    gui::ExamplePlanet,
+    widgets::{EventResponse, Widget, WidgetEvent, WidgetKind},
+};
+const TEXT_SIZE: u32 = 22;
+const LINE_HEIGHT: f32 = 26.0;
+const WIDTH: f32 = 260.0;
+//lines the status always has, before the help hint
+const LINES: usize = 7;
+//what the example planet and the simulation are set to, and whether it is running
+#[derive(Debug)]
+pub struct Status {
+    text: String,
+    //how to open the help, if there is a key for it
+    hint: Option<String>,
+    position: Vector2f,
+    layer: usize,
+}
+impl Status {
+    pub fn new(hint: Option<String>, layer: usize) -> Status {
+        Status {
+            text: String::new(),
+            hint,
+            position: Vector2f::new(0.0, 0.0),
+            layer,
+        }
+    }
+}
+impl Widget for Status {
+    fn get_bounds(&self) -> (Vector2f, Vector2f) {
+        (self.position, self.position + self.size())
+    }
+
+    fn get_layer(&self) -> usize {
+        self.layer
+    }
+
+    //always as big as its longest text, so the panel doesn't jump around as numbers change
+    fn size(&self) -> Vector2f {
+        let lines = LINES + self.hint.is_some() as usize;
+        Vector2f::new(WIDTH, lines as f32 * LINE_HEIGHT)
+    }
+
+    fn set_position(&mut self, position: Vector2f) {
+        self.position = position;
+    }
+
+    fn draw(&self, target: &mut dyn RenderTarget, font: &Font) {
+        //a line at a time, so they are spaced the same as `size` expects whatever the font
+        for (i, line) in self.text.lines().enumerate() {
+            let mut text = Text::new(line, font, TEXT_SIZE);
+            text.set_position(self.position + Vector2f::new(0.0, i as f32 * LINE_HEIGHT));
+            target.draw(&text);
+        }
+    }
+
+    fn widget_type(&self) -> WidgetKind {
+        WidgetKind::Label
+    }
+
+    fn handle_event(&mut self, _event: &WidgetEvent) -> EventResponse {
+        EventResponse::Ignored
+    }
+
+    fn debug_string(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn sync(&mut self, example: &ExamplePlanet, space: &WorldSpace) {
+        let status = if space.is_stopped() {
+            "paused"
+        } else {
+            "running"
+        };
+        self.text = format!(
+            "mass: {}  radius: {}\nt = {:.2}\nG = {:.2}\ndt = {:.3}\nsteps/tick = {}\nticks/s = {}\n{}",
+            example.mass(),
+            example.radius(),
+            space.sim_time(),
+            space.gravity(),
+            space.dt(),
+            space.substeps(),
+            space.tick_rate(),
+            status
+        );
+        if let Some(hint) = &self.hint {
+            self.text.push('\n');
+            self.text.push_str(hint);
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use sfml::{
+    graphics::{Color, Font, RectangleShape, RenderTarget, Shape, Text, Transformable},
+    system::Vector2f,
+    window::Key,
+};
+
+use crate::{
+    bodies::WorldSpace,
+    gui::ExamplePlanet,
+    widgets::{Binding, EventResponse, Widget, WidgetAction, WidgetEvent, WidgetKind},
+};
+const TEXT_SIZE: u32 = 20;
+const PADDING: f32 = 4.0;
+//a box that a number for `binding` can be typed into, applied when return is pressed
+#[derive(Debug)]
+pub struct TextBox {
+    binding: Binding,
+    buffer: String,
+    position: Vector2f,
+    dimensions: Vector2f,
+    layer: usize,
+    focused: bool,
+    rejected: bool,
+}
+impl TextBox {
+    pub fn new<T: Into<Vector2f>>(binding: Binding, layer: usize, pos: T, dims: T) -> TextBox {
+        TextBox {
+            binding,
+            buffer: String::new(),
+            position: pos.into(),
+            dimensions: dims.into(),
+            layer,
+            focused: false,
+            rejected: false,
+        }
+    }
+    fn submit(&mut self) -> EventResponse {
+        match self.buffer.parse::<f32>() {
+            Ok(value) if self.binding.accepts(value) => EventResponse::Consumed(vec![
+                WidgetAction::Set(self.binding, value),
+                WidgetAction::ReleaseFocus,
+            ]),
+            _ => {
+                self.rejected = true;
+                EventResponse::consumed()
+            }
+        }
+    }
+}
+impl Widget for TextBox {
+    fn get_bounds(&self) -> (Vector2f, Vector2f) {
+        (self.position, self.position + self.dimensions)
+    }
+
+    fn get_layer(&self) -> usize {
+        self.layer
+    }
+
+    fn size(&self) -> Vector2f {
+        self.dimensions
+    }
+
+    fn set_position(&mut self, position: Vector2f) {
+        self.position = position;
+    }
+
+    fn draw(&self, target: &mut dyn RenderTarget, font: &Font) {
+        let mut background = RectangleShape::with_size(self.dimensions);
+        background.set_position(self.position);
+        background.set_fill_color(Color::rgb(40, 40, 40));
+        background.set_outline_thickness(2.0);
+        background.set_outline_color(if self.rejected {
+            Color::RED
+        } else if self.focused {
+            Color::WHITE
+        } else {
+            Color::rgb(100, 100, 100)
+        });
+        target.draw(&background);
+        let shown = if self.focused {
+            format!("{}_", self.buffer)
+        } else {
+            self.buffer.clone()
+        };
+        let mut text = Text::new(&shown, font, TEXT_SIZE);
+        text.set_position((self.position.x + PADDING, self.position.y + PADDING / 2.0));
+        target.draw(&text);
+    }
+
+    fn widget_type(&self) -> WidgetKind {
+        WidgetKind::TextBox
+    }
+
+    fn handle_event(&mut self, event: &WidgetEvent) -> EventResponse {
+        match *event {
+            WidgetEvent::MouseDown { .. } => EventResponse::consumed(),
+            WidgetEvent::FocusGained => {
+                self.focused = true;
+                self.buffer.clear();
+                EventResponse::consumed()
+            }
+            WidgetEvent::FocusLost => {
+                self.focused = false;
+                self.rejected = false;
+                EventResponse::consumed()
+            }
+            WidgetEvent::Text(c) if self.focused => {
+                if c.is_ascii_digit()
+                    || (c == '.' && !self.buffer.contains('.'))
+                    || (c == '-' && self.buffer.is_empty())
+                {
+                    self.buffer.push(c);
+                    self.rejected = false;
+                }
+                EventResponse::consumed()
+            }
+            WidgetEvent::Key { code, .. } if self.focused => match code {
+                Key::Return => self.submit(),
+                Key::Escape => EventResponse::Consumed(vec![WidgetAction::ReleaseFocus]),
+                Key::BackSpace => {
+                    self.buffer.pop();
+                    self.rejected = false;
+                    EventResponse::consumed()
+                }
+                //every other key is swallowed so typing doesn't trigger hotkeys
+                _ => EventResponse::consumed(),
+            },
+            _ => EventResponse::Ignored,
+        }
+    }
+
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+
+    fn debug_string(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn tooltip(&self) -> Option<String> {
+        Some(self.binding.description().to_string())
+    }
+
+    fn binding(&self) -> Option<Binding> {
+        Some(self.binding)
+    }
+
+    fn sync(&mut self, example: &ExamplePlanet, space: &WorldSpace) {
+        if !self.focused {
+            self.buffer = self.binding.format(self.binding.get(example, space));
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{presets::PRESETS, triggers::Trigger};
+const USAGE: &str = "usage: orbit [--preset NAME] [--trigger \"stop time 100\"]... [--headless] \
+                     [--max-time T] [--record FILE.csv] [--save FILE.json] [--print-events] [--list-presets] [--help]
+
+triggers are pause|stop followed by collision, time T, escape ID, energy LIMIT or near A B DISTANCE.
+headless runs end at --max-time or the first time trigger, if nothing else stops them first";
+//what was asked for on the command line
+#[derive(Debug, Default)]
+pub struct Args {
+    pub preset: Option<String>,
+    //print everything that happens in the world as it happens
+    pub print_events: bool,
+    //added to whatever triggers the world already has
+    pub triggers: Vec<Trigger>,
+    //run without a window until a trigger goes off
+    pub headless: bool,
+    //when a headless run gives up if no trigger has gone off
+    pub max_time: Option<f64>,
+    pub record: Option<String>,
+    //where a headless run saves the world when it ends
+    pub save: Option<String>,
+    //print something and quit instead of opening the window
+    pub exit_message: Option<String>,
+}
+impl Args {
+    //`args` shouldn't include the program name
+    pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Args, String> {
+        let mut parsed = Args::default();
+        while let Some(arg) = args.next() {
+            let (flag, value) = match arg.find('=') {
+                Some(i) => (arg[..i].to_string(), Some(arg[i + 1..].to_string())),
+                None => (arg.clone(), None),
+            };
+            match flag.as_str() {
+                "--preset" => {
+                    let name = value
+                        .or_else(|| args.next())
+                        .ok_or_else(|| format!("--preset needs a name\n{}", USAGE))?;
+                    if !PRESETS.iter().any(|(preset, _)| *preset == name) {
+                        return Err(format!(
+                            "there is no preset called {}\n{}",
+                            name,
+                            preset_list()
+                        ));
+                    }
+                    parsed.preset = Some(name);
+                }
+                "--trigger" => {
+                    let trigger = value
+                        .or_else(|| args.next())
+                        .ok_or_else(|| format!("--trigger needs a condition\n{}", USAGE))?;
+                    let words: Vec<&str> = trigger.split_whitespace().collect();
+                    parsed.triggers.push(Trigger::parse(&words)?);
+                }
+                "--headless" => parsed.headless = true,
+                "--record" => {
+                    parsed.record = Some(
+                        value
+                            .or_else(|| args.next())
+                            .ok_or_else(|| format!("--record needs a file\n{}", USAGE))?,
+                    )
+                }
+                "--max-time" => {
+                    let time = value
+                        .or_else(|| args.next())
+                        .ok_or_else(|| format!("--max-time needs a time\n{}", USAGE))?;
+                    match time.parse::<f64>() {
+                        Ok(time) if time.is_finite() => parsed.max_time = Some(time),
+                        _ => return Err(format!("{} isn't a time\n{}", time, USAGE)),
+                    }
+                }
+                "--save" => {
+                    parsed.save = Some(
+                        value
+                            .or_else(|| args.next())
+                            .ok_or_else(|| format!("--save needs a file\n{}", USAGE))?,
+                    )
+                }
+                "--print-events" => parsed.print_events = true,
+                "--list-presets" => parsed.exit_message = Some(preset_list()),
+                "--help" | "-h" => {
+                    parsed.exit_message = Some(format!("{}\n\n{}", USAGE, preset_list()))
+                }
+                _ => return Err(format!("unknown argument {}\n{}", arg, USAGE)),
+            }
+        }
+        if (parsed.save.is_some() || parsed.max_time.is_some()) && !parsed.headless {
+            return Err(format!(
+                "--save and --max-time only work with --headless\n{}",
+                USAGE
+            ));
+        }
+        Ok(parsed)
+    }
+}
+fn preset_list() -> String {
+    let lines: Vec<String> = PRESETS
+        .iter()
+        .map(|(name, about)| format!("  {:<14}{}", name, about))
+        .collect();
+    format!("presets:\n{}", lines.join("\n"))
+}
@@ -0,0 +1,120 @@
+use std::{collections::BTreeMap, fs::read_to_string, io::ErrorKind, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gui::{
+        MAX_EXAMPLE_MASS, MAX_EXAMPLE_RADIUS, MIN_EXAMPLE_MASS, MIN_EXAMPLE_RADIUS, PANEL_TITLES,
+    },
+    keybindings::{KeyAction, Keybindings},
+    layout::Anchor,
+};
+pub const CONFIG_PATH: &str = "config.json";
+//what new planets start out as
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SpawnDefaults {
+    pub mass: f32,
+    pub radius: f32,
+    pub color_rgb: (u8, u8, u8),
+    pub immovable: bool,
+}
+impl Default for SpawnDefaults {
+    fn default() -> Self {
+        SpawnDefaults {
+            mass: 30.0,
+            radius: 30.0,
+            color_rgb: (255, 255, 255),
+            immovable: false,
+        }
+    }
+}
+//changes to one of the gui panels, anything left out keeps its default
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PanelConfig {
+    pub title: String,
+    pub anchor: Option<Anchor>,
+    pub collapsed: Option<bool>,
+    pub margin: Option<f32>,
+    pub padding: Option<f32>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    //0 means no limit
+    pub frame_rate_limit: u32,
+    pub spawn: SpawnDefaults,
+    //the keys for each action mentioned here replace its default keys
+    pub keybindings: BTreeMap<KeyAction, Vec<String>>,
+    pub panels: Vec<PanelConfig>,
+}
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            frame_rate_limit: 60,
+            spawn: SpawnDefaults::default(),
+            keybindings: BTreeMap::new(),
+            panels: Vec::new(),
+        }
+    }
+}
+impl Config {
+    //a missing file just means the defaults, anything else wrong with it is an error
+    pub fn load<T: AsRef<Path>>(p: T) -> Result<(Config, Keybindings), Vec<String>> {
+        let path = p.as_ref();
+        let raw = match read_to_string(path) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                return Ok((Config::default(), Keybindings::default()))
+            }
+            Err(e) => return Err(vec![format!("couldn't read {}: {}", path.display(), e)]),
+        };
+        let config = serde_json::from_str::<Config>(&raw)
+            .map_err(|e| vec![format!("{} isn't a valid config: {}", path.display(), e)])?;
+        let keybindings = config.validate()?;
+        Ok((config, keybindings))
+    }
+    pub fn validate(&self) -> Result<Keybindings, Vec<String>> {
+        let mut errors = Vec::new();
+        if !(MIN_EXAMPLE_MASS..=MAX_EXAMPLE_MASS).contains(&self.spawn.mass) {
+            errors.push(format!(
+                "spawn.mass must be between {} and {}, got {}",
+                MIN_EXAMPLE_MASS, MAX_EXAMPLE_MASS, self.spawn.mass
+            ));
+        }
+        if !(MIN_EXAMPLE_RADIUS..=MAX_EXAMPLE_RADIUS).contains(&self.spawn.radius) {
+            errors.push(format!(
+                "spawn.radius must be between {} and {}, got {}",
+                MIN_EXAMPLE_RADIUS, MAX_EXAMPLE_RADIUS, self.spawn.radius
+            ));
+        }
+        for panel in &self.panels {
+            if !PANEL_TITLES.contains(&panel.title.as_str()) {
+                errors.push(format!(
+                    "there is no panel called \"{}\", the panels are {:?}",
+                    panel.title, PANEL_TITLES
+                ));
+            }
+            for (name, value) in [("margin", panel.margin), ("padding", panel.padding)].iter() {
+                if let Some(value) = value {
+                    if !value.is_finite() || *value < 0.0 {
+                        errors.push(format!(
+                            "the {} of panel \"{}\" can't be negative, got {}",
+                            name, panel.title, value
+                        ));
+                    }
+                }
+            }
+        }
+        let keybindings = Keybindings::from_overrides(&self.keybindings);
+        if let Err(e) = &keybindings {
+            errors.extend(e.iter().cloned());
+        }
+        if errors.is_empty() {
+            Ok(keybindings.unwrap())
+        } else {
+            Err(errors)
+        }
+    }
+}
@@ -0,0 +1,733 @@
+use std::{collections::VecDeque, fs::read_dir};
+
+use sfml::{
+    graphics::{Color, Font, RectangleShape, RenderTarget, Shape, Text, Transformable},
+    system::Vector2f,
+    window::Key,
+};
+
+use crate::{
+    bodies::{ForceLaw, SpaceBody, WorldSpace},
+    boundary::{Boundary, BoundaryKind},
+    constraints::Link,
+    forces::{Coulomb, Drag, ForceSpec, UniformField},
+    generators::{self, GeneratorParams, GENERATORS},
+    gui::Gui,
+    orbits::Fate,
+    presets::{self, PRESETS},
+    triggers::{Trigger, TRIGGER_USAGE},
+    widgets::Binding,
+};
+const TEXT_SIZE: u32 = 20;
+const LINE_HEIGHT: f32 = 24.0;
+const MAX_OUTPUT: usize = 200;
+const MAX_HISTORY: usize = 100;
+//the console covers this much of the window from the top
+const HEIGHT_FRACTION: f32 = 0.4;
+//usages of commands that their own error messages repeat
+const FORCE_USAGE: &str = "force [add gravity|coulomb k|field x y|drag k|drag2 k] [remove n] [clear]  - lists or changes what pushes the bodies around, link joins bodies with springs";
+const LINK_USAGE: &str = "link [rod a b [snap]|spring a b k [damping] [snap]] [remove n] [clear]  - lists or joins bodies by index at the distance they are apart, snap is how far they stretch before breaking, like 0.5";
+const BOUNDARY_USAGE: &str = "boundary [none|reflective|periodic|absorbing] [left top width height] [log]  - shows or changes the edge of the world, log lists bodies it took";
+//every command with what it takes, used for `help` and tab completion
+const COMMANDS: &[(&str, &str)] = &[
+    (
+        "spawn",
+        "spawn x y vx vy mass radius  - adds a body, in world coordinates",
+    ),
+    (
+        "set",
+        "set name value  - changes a setting, like set gravity 50",
+    ),
+    ("get", "get name  - shows a setting"),
+    ("select", "select index  - follows a body"),
+    ("save", "save file.json  - saves the world"),
+    (
+        "load",
+        "load file.json  - replaces the world with a saved one",
+    ),
+    (
+        "preset",
+        "preset name  - replaces the world with a built in one",
+    ),
+    (
+        "generate",
+        "generate name [count=300] [seed=1] [size=600] [central=400] [mass=0.5..2] [radius=2..4] [particles=false]  - fills the world with made up bodies, mass and radius can be 3, 1..3 or 1..100^-2.35",
+    ),
+    (
+        "law",
+        "law [inverse_linear|inverse_square]  - shows or changes how gravity falls off",
+    ),
+    (
+        "force",
+        FORCE_USAGE,
+    ),
+    ("link", LINK_USAGE),
+    (
+        "spin",
+        "spin value  - sets how fast the followed body turns, in radians per unit of time",
+    ),
+    (
+        "charge",
+        "charge value  - sets the charge of the followed body, for coulomb forces",
+    ),
+    ("boundary", BOUNDARY_USAGE),
+    (
+        "record",
+        "record [file.csv|stop]  - writes where every body is after each tick, and what happens, to a file",
+    ),
+    (
+        "fates",
+        "fates  - which bodies are bound, captured by another or escaping",
+    ),
+    (
+        "trigger",
+        "trigger [add pause|stop condition] [remove n] [clear]  - lists or changes what pauses or ends the run, see trigger add",
+    ),
+    (
+        "step",
+        "step n  - runs n steps right away, even while paused",
+    ),
+    ("stats", "stats  - totals over every body"),
+    ("widgets", "widgets  - prints every widget, for debugging"),
+    ("clear", "clear  - empties the console"),
+    ("help", "help  - lists the commands"),
+];
+//what `force add` can add
+const FORCE_KINDS: &[&str] = &["gravity", "coulomb", "field", "drag", "drag2"];
+//a drop down text console for doing things there is no button for
+#[derive(Debug, Default)]
+pub struct Console {
+    open: bool,
+    input: String,
+    output: VecDeque<String>,
+    history: Vec<String>,
+    //which history entry is being shown, counting back from the newest
+    history_pos: Option<usize>,
+    //the key that opened the console also types a character, which shouldn't end up in the input
+    skip_text: bool,
+}
+impl Console {
+    pub fn new() -> Self {
+        let mut console = Console::default();
+        console.print("Type help for a list of commands, tab completes");
+        console
+    }
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.skip_text = true;
+    }
+    fn print<T: Into<String>>(&mut self, line: T) {
+        for line in line.into().lines() {
+            self.output.push_back(line.to_string());
+        }
+        while self.output.len() > MAX_OUTPUT {
+            self.output.pop_front();
+        }
+    }
+    pub fn text_entered(&mut self, c: char) {
+        if self.skip_text {
+            self.skip_text = false;
+            return;
+        }
+        if !c.is_control() {
+            self.input.push(c);
+        }
+    }
+    //returns a command line once return is pressed
+    pub fn key_pressed(&mut self, code: Key) -> Option<String> {
+        self.skip_text = false;
+        match code {
+            Key::Return => {
+                let line = self.input.trim().to_string();
+                self.input.clear();
+                self.history_pos = None;
+                if line.is_empty() {
+                    return None;
+                }
+                if self.history.last() != Some(&line) {
+                    self.history.push(line.clone());
+                    if self.history.len() > MAX_HISTORY {
+                        self.history.remove(0);
+                    }
+                }
+                self.print(format!("> {}", line));
+                return Some(line);
+            }
+            Key::BackSpace => {
+                self.input.pop();
+            }
+            Key::Escape => self.open = false,
+            Key::Up => self.browse_history(true),
+            Key::Down => self.browse_history(false),
+            Key::Tab => self.complete(),
+            _ => {}
+        }
+        None
+    }
+    fn browse_history(&mut self, older: bool) {
+        if self.history.is_empty() {
+            return;
+        }
+        self.history_pos = match (self.history_pos, older) {
+            (None, true) => Some(0),
+            (None, false) => None,
+            (Some(pos), true) => Some((pos + 1).min(self.history.len() - 1)),
+            (Some(0), false) => None,
+            (Some(pos), false) => Some(pos - 1),
+        };
+        self.input = match self.history_pos {
+            Some(pos) => self.history[self.history.len() - 1 - pos].clone(),
+            None => String::new(),
+        };
+    }
+    //fills in as much of the last word as every match agrees on, and lists them if there are several
+    fn complete(&mut self) {
+        let words: Vec<&str> = self.input.split(' ').collect();
+        let (last, before) = words.split_last().unwrap();
+        let candidates: Vec<String> = match before {
+            [] => COMMANDS.iter().map(|(name, _)| name.to_string()).collect(),
+            ["set"] | ["get"] => Binding::ALL.iter().map(|b| b.key().to_string()).collect(),
+            ["save"] | ["load"] => json_files(),
+            ["record"] => std::iter::once("stop".to_string())
+                .chain(csv_files())
+                .collect(),
+            ["preset"] => PRESETS.iter().map(|(name, _)| name.to_string()).collect(),
+            ["generate"] => GENERATORS
+                .iter()
+                .map(|(name, _)| name.to_string())
+                .collect(),
+            ["law"] => ForceLaw::ALL.iter().map(|l| l.name().to_string()).collect(),
+            ["force"] => ["add", "remove", "clear"]
+                .iter()
+                .map(|w| w.to_string())
+                .collect(),
+            ["force", "add"] => FORCE_KINDS.iter().map(|k| k.to_string()).collect(),
+            ["boundary"] => BoundaryKind::ALL
+                .iter()
+                .map(|k| k.name())
+                .chain(std::iter::once("log"))
+                .map(|w| w.to_string())
+                .collect(),
+            ["trigger"] => ["add", "remove", "clear"]
+                .iter()
+                .map(|w| w.to_string())
+                .collect(),
+            ["trigger", "add"] => ["pause", "stop"].iter().map(|w| w.to_string()).collect(),
+            ["trigger", "add", _] => ["collision", "time", "escape", "energy", "near"]
+                .iter()
+                .map(|w| w.to_string())
+                .collect(),
+            ["link"] => ["rod", "spring", "remove", "clear"]
+                .iter()
+                .map(|w| w.to_string())
+                .collect(),
+            _ => Vec::new(),
+        };
+        let matches: Vec<&String> = candidates.iter().filter(|c| c.starts_with(last)).collect();
+        if matches.is_empty() {
+            return;
+        }
+        let mut common = matches[0].clone();
+        for other in &matches[1..] {
+            while !other.starts_with(&common) {
+                common.pop();
+            }
+        }
+        let mut completed = before.join(" ");
+        if !completed.is_empty() {
+            completed.push(' ');
+        }
+        completed.push_str(&common);
+        if matches.len() == 1 {
+            completed.push(' ');
+        } else {
+            let listed: Vec<&str> = matches.iter().map(|m| m.as_str()).collect();
+            self.print(listed.join("  "));
+        }
+        self.input = completed;
+    }
+    pub fn execute<'a>(
+        &mut self,
+        line: &str,
+        space: &mut WorldSpace<'a>,
+        gui: &mut Gui<'a>,
+        view_size: Vector2f,
+    ) {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let result = match words.as_slice() {
+            ["spawn", args @ ..] => spawn(args, space, gui),
+            ["set", name, value] => match (Binding::from_key(name), value.parse::<f32>()) {
+                (None, _) => Err(format!("there is no setting called {}", name)),
+                (_, Err(_)) => Err(format!("{} isn't a number", value)),
+                (Some(binding), Ok(value)) => {
+                    if gui.set_binding(binding, value, space) {
+                        Ok(format!("{} = {}", binding.key(), binding.format(value)))
+                    } else {
+                        Err(format!("{} can't be {}", binding.key(), value))
+                    }
+                }
+            },
+            ["get", name] => match Binding::from_key(name) {
+                Some(binding) => {
+                    let value = binding.get(gui.example(), space);
+                    Ok(format!("{} = {}", binding.key(), binding.format(value)))
+                }
+                None => Err(format!("there is no setting called {}", name)),
+            },
+            ["select", index] => match index.parse::<usize>() {
+                Ok(index) if index < space.bodies.len() => {
+                    space.focused_idx = Some(index);
+                    Ok(format!("following body {}", index))
+                }
+                _ => Err(format!(
+                    "{} isn't a body, there are {}",
+                    index,
+                    space.bodies.len()
+                )),
+            },
+            ["save", path] => space
+                .serialize(path)
+                .map(|_| format!("saved to {}", path))
+                .map_err(|e| format!("couldn't save to {}: {}", path, e)),
+            ["load", path] => match WorldSpace::deserialize(path) {
+                Ok(mut loaded) => {
+                    loaded.set_view_size(view_size);
+                    loaded.keep_subscribers(space);
+                    *space = loaded;
+                    Ok(format!("loaded {}", path))
+                }
+                Err(e) => Err(format!("couldn't load {}: {}", path, e)),
+            },
+            ["preset"] => Ok(PRESETS
+                .iter()
+                .map(|(name, about)| format!("{:<14}{}", name, about))
+                .collect::<Vec<String>>()
+                .join("\n")),
+            ["preset", name] => presets::apply(name, space),
+            ["generate"] => Ok(GENERATORS
+                .iter()
+                .map(|(name, about)| format!("{:<14}{}", name, about))
+                .collect::<Vec<String>>()
+                .join("\n")),
+            ["generate", name, settings @ ..] => GeneratorParams::parse(settings)
+                .and_then(|params| generators::generate(name, &params, space)),
+            ["law"] => Ok(format!("force law is {}", space.force_law().name())),
+            ["law", name] => match ForceLaw::from_name(name) {
+                Some(law) => {
+                    space.set_force_law(law);
+                    Ok(format!("force law is now {}", law.name()))
+                }
+                None => Err(format!(
+                    "there is no force law called {}, try inverse_linear or inverse_square",
+                    name
+                )),
+            },
+            ["force", args @ ..] => force(args, space),
+            ["link", args @ ..] => link(args, space),
+            ["boundary", args @ ..] => boundary(args, space),
+            ["spin", value] => match (space.focused_idx, value.parse::<f32>()) {
+                (None, _) => Err("no body is being followed".to_string()),
+                (_, Err(_)) => Err(format!("{} isn't a number", value)),
+                (Some(index), Ok(spin)) => match space.bodies.get_mut(index) {
+                    Some(body) => {
+                        body.set_spin(spin);
+                        Ok(format!("body {} spins at {}", index, spin))
+                    }
+                    None => Err(format!("the followed body {} is gone", index)),
+                },
+            },
+            ["charge", value] => match (space.focused_idx, value.parse::<f32>()) {
+                (None, _) => Err("no body is being followed".to_string()),
+                (_, Err(_)) => Err(format!("{} isn't a number", value)),
+                (Some(index), Ok(charge)) => match space.bodies.get_mut(index) {
+                    Some(body) => {
+                        body.set_charge(charge);
+                        Ok(format!("body {} has charge {}", index, charge))
+                    }
+                    None => Err(format!("the followed body {} is gone", index)),
+                },
+            },
+            ["record"] => Ok(match space.recording() {
+                Some(path) => format!("recording to {}", path),
+                None => "not recording".to_string(),
+            }),
+            ["record", "stop"] => match space.stop_recording() {
+                Some(path) => Ok(format!("stopped recording to {}", path)),
+                None => Err("nothing is being recorded".to_string()),
+            },
+            ["record", path] => space
+                .start_recording(path)
+                .map(|_| format!("recording to {}", path))
+                .map_err(|e| format!("couldn't record to {}: {}", path, e)),
+            ["fates"] => Ok(space
+                .bodies
+                .iter()
+                .enumerate()
+                .map(|(i, body)| {
+                    let fate = match body.fate() {
+                        None => "not looked at yet".to_string(),
+                        Some(Fate::Bound) => "bound".to_string(),
+                        Some(Fate::Escaping) => "escaping".to_string(),
+                        Some(Fate::Captured(by)) => format!("captured by body {}", by),
+                    };
+                    format!("{} (body {}): {}", i, body.id(), fate)
+                })
+                .collect::<Vec<String>>()
+                .join("\n")),
+            ["trigger", args @ ..] => trigger(args, space),
+            ["step", steps] => match steps.parse::<u32>() {
+                Ok(steps) => {
+                    let ran = space.run_steps(steps);
+                    Ok(format!("ran {} steps, t = {:.2}", ran, space.sim_time()))
+                }
+                Err(_) => Err(format!("{} isn't a whole number of steps", steps)),
+            },
+            ["stats"] => {
+                let stats = space.stats();
+                Ok(format!(
+                    "bodies: {}\nparticles: {}\ntotal mass: {:.2}\ncentre of mass: ({:.1}, {:.1})\n\
+                     momentum: ({:.2}, {:.2})\nangular momentum: {:.2}\nkinetic energy: {:.2}\n\
+                     total energy: {:.2}\nt = {:.2}",
+                    stats.bodies,
+                    stats.particles,
+                    stats.mass,
+                    stats.center_of_mass.x,
+                    stats.center_of_mass.y,
+                    stats.momentum.x,
+                    stats.momentum.y,
+                    stats.angular_momentum,
+                    stats.kinetic_energy,
+                    space.total_energy(),
+                    space.sim_time()
+                ))
+            }
+            ["widgets"] => Ok(gui
+                .widgets
+                .iter()
+                .map(|w| format!("{:?}", w.borrow()))
+                .collect::<Vec<String>>()
+                .join("\n")),
+            ["clear"] => {
+                self.output.clear();
+                Ok(String::new())
+            }
+            ["help"] => Ok(COMMANDS
+                .iter()
+                .map(|(_, usage)| *usage)
+                .collect::<Vec<&str>>()
+                .join("\n")),
+            [command, ..] => match COMMANDS.iter().find(|(name, _)| name == command) {
+                Some((_, usage)) => Err(format!("usage: {}", usage)),
+                None => Err(format!("unknown command {}, try help", command)),
+            },
+            [] => Ok(String::new()),
+        };
+        match result {
+            Ok(message) => self.print(message),
+            Err(message) => self.print(format!("error: {}", message)),
+        }
+    }
+    pub fn draw(&self, target: &mut dyn RenderTarget, font: &Font, size: Vector2f) {
+        if !self.open {
+            return;
+        }
+        let height = (size.y * HEIGHT_FRACTION).max(LINE_HEIGHT * 3.0);
+        let mut background = RectangleShape::with_size(Vector2f::new(size.x, height));
+        background.set_fill_color(Color::rgba(0, 0, 0, 220));
+        background.set_outline_thickness(1.0);
+        background.set_outline_color(Color::rgb(90, 90, 90));
+        target.draw(&background);
+        let mut prompt = Text::new(&format!("> {}_", self.input), font, TEXT_SIZE);
+        prompt.set_position((8.0, height - LINE_HEIGHT - 4.0));
+        target.draw(&prompt);
+        //newest output sits just above the prompt, older lines go up until they run out of room
+        let rows = ((height - LINE_HEIGHT - 8.0) / LINE_HEIGHT) as usize;
+        for (i, line) in self.output.iter().rev().take(rows).enumerate() {
+            let mut text = Text::new(line, font, TEXT_SIZE);
+            text.set_fill_color(if line.starts_with("error:") {
+                Color::rgb(255, 120, 120)
+            } else {
+                Color::rgb(200, 200, 200)
+            });
+            text.set_position((8.0, height - LINE_HEIGHT * (i as f32 + 2.0) - 4.0));
+            target.draw(&text);
+        }
+    }
+}
+fn spawn(args: &[&str], space: &mut WorldSpace, gui: &Gui) -> Result<String, String> {
+    let numbers: Result<Vec<f32>, _> = args.iter().map(|a| a.parse::<f32>()).collect();
+    match numbers.as_deref() {
+        Ok(&[x, y, vx, vy, mass, radius]) => {
+            //one body that isn't a number makes every other one's pull not a number either
+            if [x, y, vx, vy].iter().any(|n| !n.is_finite()) {
+                return Err("position and velocity have to be numbers".to_string());
+            }
+            if !mass.is_finite() || !radius.is_finite() || mass <= 0.0 || radius <= 0.0 {
+                return Err("mass and radius have to be numbers more than 0".to_string());
+            }
+            let index = space.bodies.len();
+            space.push_body(SpaceBody::new(
+                (x, y),
+                mass,
+                radius,
+                vx,
+                vy,
+                gui.example().immovable(),
+                gui.example().shape.fill_color(),
+                index,
+            ));
+            Ok(format!("spawned body {}", index))
+        }
+        _ => Err("usage: spawn x y vx vy mass radius, all numbers".to_string()),
+    }
+}
+//lists the force models, or adds and removes them
+fn force(args: &[&str], space: &mut WorldSpace) -> Result<String, String> {
+    let numbers = |words: &[&str]| -> Result<Vec<f32>, String> {
+        words
+            .iter()
+            .map(|w| {
+                w.parse::<f32>()
+                    .map_err(|_| format!("{} isn't a number", w))
+            })
+            .collect()
+    };
+    let spec = match args {
+        [] => {
+            if space.forces().is_empty() {
+                return Ok("nothing moves the bodies".to_string());
+            }
+            return Ok(space
+                .forces()
+                .iter()
+                .enumerate()
+                .map(|(i, spec)| format!("{}: {}", i + 1, spec.describe()))
+                .collect::<Vec<String>>()
+                .join("\n"));
+        }
+        ["clear"] => {
+            space.clear_forces();
+            return Ok("removed every force".to_string());
+        }
+        ["remove", n] => {
+            return match n.parse::<usize>().ok().and_then(|n| n.checked_sub(1)) {
+                Some(i) => space
+                    .remove_force(i)
+                    .map(|spec| format!("removed {}", spec.describe()))
+                    .ok_or_else(|| format!("there is no force {}", n)),
+                None => Err(format!("{} isn't a force number", n)),
+            }
+        }
+        ["add", "gravity"] => ForceSpec::Gravity,
+        ["add", "coulomb", strength] => ForceSpec::Coulomb(Coulomb {
+            strength: numbers(&[strength])?[0],
+        }),
+        ["add", "field", x, y] => {
+            let field = numbers(&[x, y])?;
+            ForceSpec::UniformField(UniformField {
+                x: field[0],
+                y: field[1],
+            })
+        }
+        ["add", kind @ "drag", coefficient] | ["add", kind @ "drag2", coefficient] => {
+            ForceSpec::Drag(Drag {
+                coefficient: numbers(&[coefficient])?[0],
+                quadratic: *kind == "drag2",
+            })
+        }
+        _ => return Err(format!("usage: {}", FORCE_USAGE)),
+    };
+    space.add_force(spec);
+    Ok(format!("added {}", spec.describe()))
+}
+//lists what pauses or ends the run, or adds and removes triggers
+fn trigger(args: &[&str], space: &mut WorldSpace) -> Result<String, String> {
+    match args {
+        [] => {
+            if space.triggers().is_empty() {
+                return Ok("there are no triggers".to_string());
+            }
+            Ok(space
+                .triggers()
+                .iter()
+                .enumerate()
+                .map(|(i, t)| format!("{}: {}", i + 1, t.describe()))
+                .collect::<Vec<String>>()
+                .join("\n"))
+        }
+        ["clear"] => {
+            space.clear_triggers();
+            Ok("removed every trigger".to_string())
+        }
+        ["remove", n] => match n.parse::<usize>().ok().and_then(|n| n.checked_sub(1)) {
+            Some(i) => space
+                .remove_trigger(i)
+                .map(|t| format!("removed {}", t.describe()))
+                .ok_or_else(|| format!("there is no trigger {}", n)),
+            None => Err(format!("{} isn't a trigger number", n)),
+        },
+        ["add"] => Err(format!("usage: trigger add {}", TRIGGER_USAGE)),
+        ["add", words @ ..] => {
+            let trigger = Trigger::parse(words)?;
+            space.add_trigger(trigger);
+            Ok(format!("added {}", trigger.describe()))
+        }
+        _ => Err(format!(
+            "usage: trigger [add {}] [remove n] [clear]",
+            TRIGGER_USAGE
+        )),
+    }
+}
+//lists the links between bodies, or adds and removes them
+fn link(args: &[&str], space: &mut WorldSpace) -> Result<String, String> {
+    let numbers = |words: &[&str]| -> Result<Vec<f32>, String> {
+        words
+            .iter()
+            .map(|w| {
+                w.parse::<f32>()
+                    .map_err(|_| format!("{} isn't a number", w))
+            })
+            .collect()
+    };
+    let indices = |a: &str, b: &str| match (a.parse::<usize>(), b.parse::<usize>()) {
+        (Ok(a), Ok(b)) => Ok((a, b)),
+        _ => Err(format!("{} and {} have to be body indices", a, b)),
+    };
+    let (a, b, link, snap) = match args {
+        [] => {
+            if space.constraints().is_empty() {
+                return Ok("no bodies are linked".to_string());
+            }
+            return Ok(space
+                .constraints()
+                .iter()
+                .enumerate()
+                .map(|(i, c)| format!("{}: {}", i + 1, c.describe()))
+                .collect::<Vec<String>>()
+                .join("\n"));
+        }
+        ["clear"] => {
+            space.clear_constraints();
+            return Ok("removed every link".to_string());
+        }
+        ["remove", n] => {
+            return match n.parse::<usize>().ok().and_then(|n| n.checked_sub(1)) {
+                Some(i) => space
+                    .remove_constraint(i)
+                    .map(|c| format!("removed {}", c.describe()))
+                    .ok_or_else(|| format!("there is no link {}", n)),
+                None => Err(format!("{} isn't a link number", n)),
+            }
+        }
+        ["rod", a, b, snap @ ..] if snap.len() <= 1 => {
+            let (a, b) = indices(a, b)?;
+            (a, b, Link::Rod, numbers(snap)?.first().cloned())
+        }
+        ["spring", a, b, rest @ ..] if !rest.is_empty() && rest.len() <= 3 => {
+            let (a, b) = indices(a, b)?;
+            let values = numbers(rest)?;
+            let link = Link::Spring {
+                stiffness: values[0],
+                damping: values.get(1).cloned().unwrap_or(0.0),
+            };
+            (a, b, link, values.get(2).cloned())
+        }
+        _ => return Err(format!("usage: {}", LINK_USAGE)),
+    };
+    if snap.is_some_and(|snap| snap <= 0.0) {
+        return Err("links have to be able to stretch more than 0 before snapping".to_string());
+    }
+    space
+        .add_constraint(a, b, link, snap)
+        .map(|c| format!("added {}", c.describe()))
+}
+//shows or changes the boundary, or lists what went out through it
+fn boundary(args: &[&str], space: &mut WorldSpace) -> Result<String, String> {
+    let describe = |b: Boundary| {
+        format!(
+            "boundary is {}, from ({}, {}) {} wide and {} high",
+            b.kind.name(),
+            b.left,
+            b.top,
+            b.width,
+            b.height
+        )
+    };
+    let (name, area) = match args {
+        [] => return Ok(describe(space.boundary())),
+        ["log"] => {
+            if space.absorbed().is_empty() {
+                return Ok("nothing has gone out through the boundary".to_string());
+            }
+            return Ok(space
+                .absorbed()
+                .iter()
+                .map(|a| {
+                    format!(
+                        "t = {:.2}: id {}, mass {:.2}, at ({:.1}, {:.1}) going ({:.2}, {:.2})",
+                        a.time,
+                        a.id,
+                        a.mass,
+                        a.position.x,
+                        a.position.y,
+                        a.velocity.x,
+                        a.velocity.y
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join("\n"));
+        }
+        [name] => (name, None),
+        [name, left, top, width, height] => {
+            let numbers: Result<Vec<f32>, _> = [left, top, width, height]
+                .iter()
+                .map(|w| w.parse::<f32>())
+                .collect();
+            match numbers.as_deref() {
+                Ok(&[left, top, width, height]) if width > 0.0 && height > 0.0 => {
+                    (name, Some((left, top, width, height)))
+                }
+                _ => return Err("the boundary needs a position and a size over 0".to_string()),
+            }
+        }
+        _ => return Err(format!("usage: {}", BOUNDARY_USAGE)),
+    };
+    let kind = BoundaryKind::from_name(name).ok_or_else(|| {
+        format!(
+            "there is no boundary called {}, try none, reflective, periodic or absorbing",
+            name
+        )
+    })?;
+    let mut boundary = space.boundary();
+    boundary.kind = kind;
+    if let Some((left, top, width, height)) = area {
+        boundary.left = left;
+        boundary.top = top;
+        boundary.width = width;
+        boundary.height = height;
+    }
+    space.set_boundary(boundary);
+    Ok(describe(boundary))
+}
+//the saves in the working directory, for completing save and load
+fn json_files() -> Vec<String> {
+    files_ending_with(".json")
+}
+//trajectory logs in the working directory, for completing record
+fn csv_files() -> Vec<String> {
+    files_ending_with(".csv")
+}
+fn files_ending_with(extension: &str) -> Vec<String> {
+    read_dir(".")
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|name| name.ends_with(extension))
+                .collect()
+        })
+        .unwrap_or_default()
+}
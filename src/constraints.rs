@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{bodies::SpaceBody, boundary::Boundary, forces::Spring};
+//rods are put right this many times a step, since fixing one stretches its neighbours in a chain
+pub const ROD_ITERATIONS: u32 = 4;
+//how two bodies are held together
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Link {
+    //keeps the bodies exactly the length apart
+    Rod,
+    //pulls them back towards the length, harder the further they are from it
+    Spring {
+        stiffness: f32,
+        #[serde(default)]
+        damping: f32,
+    },
+}
+//two bodies joined together, found by their ids like springs so it follows them as others
+//come and go. when either end merges the link moves over to what it merged into
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Constraint {
+    pub a: u64,
+    pub b: u64,
+    pub length: f32,
+    pub link: Link,
+    //snaps once it is this share longer than its length, or never if there is none.
+    //rods only stretch during a step before they are put right, so they snap when yanked hard
+    #[serde(default)]
+    pub breaking_stretch: Option<f32>,
+}
+impl Constraint {
+    //indices of both ends, if they are both still there
+    pub fn ends(&self, bodies: &[SpaceBody]) -> Option<(usize, usize)> {
+        let find = |id| bodies.iter().position(|body| body.id() == id);
+        match (find(self.a), find(self.b)) {
+            (Some(a), Some(b)) if a != b => Some((a, b)),
+            _ => None,
+        }
+    }
+    //how much longer than its length it is, as a share of the length
+    pub fn stretch(&self, bodies: &[SpaceBody], boundary: &Boundary) -> Option<f32> {
+        let (a, b) = self.ends(bodies)?;
+        let d = boundary.separation(bodies[a].pos2f(), bodies[b].pos2f());
+        let distance = (d.x * d.x + d.y * d.y).sqrt();
+        Some(distance / self.length.max(f32::EPSILON) - 1.0)
+    }
+    pub fn is_broken(&self, bodies: &[SpaceBody], boundary: &Boundary) -> bool {
+        match (self.stretch(bodies, boundary), self.breaking_stretch) {
+            (None, _) => true,
+            (Some(stretch), Some(limit)) => stretch > limit,
+            (Some(_), None) => false,
+        }
+    }
+    //the force a spring link puts on its ends, which is just a spring force model
+    pub fn spring(&self) -> Option<Spring> {
+        match self.link {
+            Link::Rod => None,
+            Link::Spring { stiffness, damping } => Some(Spring {
+                a: self.a,
+                b: self.b,
+                stiffness,
+                rest_length: self.length,
+                damping,
+            }),
+        }
+    }
+    pub fn describe(&self) -> String {
+        let kind = match self.link {
+            Link::Rod => "rod".to_string(),
+            Link::Spring { stiffness, damping } => {
+                format!("spring, stiffness {}, damping {},", stiffness, damping)
+            }
+        };
+        let breaks = match self.breaking_stretch {
+            Some(limit) => format!("snaps past {:.0}% stretch", limit * 100.0),
+            None => "never snaps".to_string(),
+        };
+        format!(
+            "{} between ids {} and {}, length {:.1}, {}",
+            kind, self.a, self.b, self.length, breaks
+        )
+    }
+}
@@ -0,0 +1,195 @@
+use std::{
+    collections::VecDeque,
+    fmt::{self, Debug, Formatter},
+    time::Instant,
+};
+
+use sfml::{
+    graphics::{Color, Font, RenderTarget, Text, Transformable},
+    system::Vector2f,
+};
+//how many events the feed shows at once, and for how many seconds each one stays
+const FEED_LINES: usize = 6;
+const FEED_SECONDS: f32 = 8.0;
+const FEED_TEXT_SIZE: u32 = 18;
+const FEED_LINE_HEIGHT: f32 = 22.0;
+//why a body stopped existing, other than by hitting another
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Removal {
+    //someone took it out
+    Deleted,
+    //it went out through an absorbing boundary
+    LeftWorld,
+    //it was pulled apart by a heavier body
+    TornApart,
+}
+//something that happened in the world, which bodies are named by id in
+#[derive(Debug, Clone, PartialEq)]
+pub enum SimEvent {
+    //two bodies touched, `speed` being how fast they were going towards each other
+    Collided {
+        a: u64,
+        b: u64,
+        speed: f32,
+        time: f64,
+    },
+    //what two bodies that collided turned into, one body unless the impact shattered them
+    Merged {
+        a: u64,
+        b: u64,
+        products: Vec<u64>,
+        time: f64,
+    },
+    Removed {
+        id: u64,
+        reason: Removal,
+        time: f64,
+    },
+    Spawned {
+        id: u64,
+        time: f64,
+    },
+    //every body was swapped out at once, for a preset, a generator or clearing the world
+    Replaced {
+        bodies: usize,
+        time: f64,
+    },
+    Escaped {
+        id: u64,
+        time: f64,
+    },
+    Captured {
+        id: u64,
+        by: u64,
+        time: f64,
+    },
+    //a setting that changes how the world moves, with what it is now
+    ParameterChanged {
+        name: &'static str,
+        value: String,
+        time: f64,
+    },
+    //a trigger went off, paused or stopping the world
+    Triggered {
+        trigger: String,
+        time: f64,
+    },
+}
+impl SimEvent {
+    pub fn time(&self) -> f64 {
+        match self {
+            SimEvent::Collided { time, .. }
+            | SimEvent::Merged { time, .. }
+            | SimEvent::Removed { time, .. }
+            | SimEvent::Spawned { time, .. }
+            | SimEvent::Replaced { time, .. }
+            | SimEvent::Escaped { time, .. }
+            | SimEvent::Captured { time, .. }
+            | SimEvent::ParameterChanged { time, .. }
+            | SimEvent::Triggered { time, .. } => *time,
+        }
+    }
+    pub fn describe(&self) -> String {
+        let time = self.time();
+        match self {
+            SimEvent::Collided { a, b, speed, .. } => format!(
+                "bodies {} and {} collided at {:.2} at t={:.2}",
+                a, b, speed, time
+            ),
+            SimEvent::Merged { a, b, products, .. } => {
+                let products: Vec<String> = products.iter().map(|id| id.to_string()).collect();
+                if products.len() == 1 {
+                    format!(
+                        "bodies {} and {} merged into body {} at t={:.2}",
+                        a, b, products[0], time
+                    )
+                } else {
+                    format!(
+                        "bodies {} and {} shattered into bodies {} at t={:.2}",
+                        a,
+                        b,
+                        products.join(", "),
+                        time
+                    )
+                }
+            }
+            SimEvent::Removed { id, reason, .. } => {
+                let how = match reason {
+                    Removal::Deleted => "was deleted",
+                    Removal::LeftWorld => "left the world",
+                    Removal::TornApart => "was torn apart",
+                };
+                format!("body {} {} at t={:.2}", id, how, time)
+            }
+            SimEvent::Spawned { id, .. } => format!("body {} spawned at t={:.2}", id, time),
+            SimEvent::Replaced { bodies, .. } => {
+                format!("the world was replaced with {} bodies", bodies)
+            }
+            SimEvent::Escaped { id, .. } => format!("body {} escaped at t={:.2}", id, time),
+            SimEvent::Captured { id, by, .. } => {
+                format!("body {} captured by body {} at t={:.2}", id, by, time)
+            }
+            SimEvent::ParameterChanged { name, value, .. } => {
+                format!("{} changed to {} at t={:.2}", name, value, time)
+            }
+            SimEvent::Triggered { trigger, .. } => {
+                format!("triggered at t={:.2}: {}", time, trigger)
+            }
+        }
+    }
+}
+pub type Callback<'a> = Box<dyn FnMut(&SimEvent) + 'a>;
+//things told about every event as it happens, in the order they were added
+#[derive(Default)]
+pub struct Subscribers<'a> {
+    callbacks: Vec<Callback<'a>>,
+}
+impl<'a> Subscribers<'a> {
+    pub fn add(&mut self, callback: Callback<'a>) {
+        self.callbacks.push(callback);
+    }
+    pub fn notify(&mut self, event: &SimEvent) {
+        for callback in self.callbacks.iter_mut() {
+            callback(event);
+        }
+    }
+}
+impl Debug for Subscribers<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Subscribers({})", self.callbacks.len())
+    }
+}
+//the last few events in the bottom left corner, fading out as they get old
+#[derive(Debug, Default)]
+pub struct EventFeed {
+    lines: VecDeque<(String, Instant)>,
+}
+impl EventFeed {
+    //spawns, collisions and settings changing happen too often to be worth showing, and the
+    //merge that follows a collision says more
+    pub fn push(&mut self, event: &SimEvent) {
+        if let SimEvent::Spawned { .. }
+        | SimEvent::Collided { .. }
+        | SimEvent::ParameterChanged { .. } = event
+        {
+            return;
+        }
+        self.lines.push_back((event.describe(), Instant::now()));
+        while self.lines.len() > FEED_LINES {
+            self.lines.pop_front();
+        }
+    }
+    pub fn draw(&mut self, target: &mut dyn RenderTarget, font: &Font, size: Vector2f) {
+        self.lines
+            .retain(|(_, shown)| shown.elapsed().as_secs_f32() < FEED_SECONDS);
+        for (i, (line, shown)) in self.lines.iter().rev().enumerate() {
+            //fully there for the first half, then fading away
+            let left = 1.0 - shown.elapsed().as_secs_f32() / FEED_SECONDS;
+            let alpha = (left * 2.0).min(1.0) * 255.0;
+            let mut text = Text::new(line, font, FEED_TEXT_SIZE);
+            text.set_fill_color(Color::rgba(230, 230, 160, alpha as u8));
+            text.set_position((10.0, size.y - FEED_LINE_HEIGHT * (i as f32 + 1.0) - 10.0));
+            target.draw(&text);
+        }
+    }
+}
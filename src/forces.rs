@@ -0,0 +1,250 @@
+use std::fmt::Debug;
+
+use serde::{Deserialize, Serialize};
+use sfml::system::Vector2f;
+
+use crate::{
+    bodies::{ForceLaw, SpaceBody},
+    boundary::Boundary,
+};
+//keeps charges sitting on top of each other from pushing infinitely hard
+const COULOMB_SOFTENING: f32 = 0.0225;
+//bodies closer than this on either axis don't pull on each other, which keeps ones sitting on
+//top of each other from pulling infinitely hard
+const GRAVITY_MARGIN: f32 = 1.0;
+//whether two bodies `d` apart pull on each other
+pub fn pulls(d: Vector2f) -> bool {
+    d.x.abs() > GRAVITY_MARGIN && d.y.abs() > GRAVITY_MARGIN
+}
+//something that pushes or pulls on bodies, several of which can act on a world at once
+pub trait ForceModel: Debug {
+    //adds the acceleration this gives each body to the one at the same index in `accelerations`.
+    //the way between two bodies is `boundary.separation`, which goes across the edges of a
+    //periodic world
+    fn accelerate(&self, bodies: &[SpaceBody], boundary: &Boundary, accelerations: &mut [Vector2f]);
+    //test particles have no mass or charge, so only some models move them
+    fn accelerate_particle(
+        &self,
+        _bodies: &[SpaceBody],
+        _boundary: &Boundary,
+        _position: Vector2f,
+        _velocity: Vector2f,
+    ) -> Vector2f {
+        Vector2f::new(0.0, 0.0)
+    }
+}
+//every body pulling on every other, with the world's gravity and force law
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gravity {
+    pub constant: f32,
+    pub law: ForceLaw,
+}
+impl Gravity {
+    fn pull(
+        &self,
+        bodies: &[SpaceBody],
+        boundary: &Boundary,
+        position: Vector2f,
+        skip: Option<usize>,
+    ) -> Vector2f {
+        let power = (self.law.exponent() + 1.0) / 2.0;
+        let mut acceleration = Vector2f::new(0.0, 0.0);
+        for (i, other) in bodies.iter().enumerate() {
+            if Some(i) == skip {
+                continue;
+            }
+            let d = boundary.separation(position, other.pos2f());
+            if !pulls(d) {
+                continue;
+            }
+            let squared = d.x * d.x + d.y * d.y;
+            acceleration += d * (self.constant * other.mass() / squared.powf(power));
+        }
+        acceleration
+    }
+}
+impl ForceModel for Gravity {
+    fn accelerate(
+        &self,
+        bodies: &[SpaceBody],
+        boundary: &Boundary,
+        accelerations: &mut [Vector2f],
+    ) {
+        for (i, body) in bodies.iter().enumerate() {
+            accelerations[i] += self.pull(bodies, boundary, body.pos2f(), Some(i));
+        }
+    }
+    fn accelerate_particle(
+        &self,
+        bodies: &[SpaceBody],
+        boundary: &Boundary,
+        position: Vector2f,
+        _velocity: Vector2f,
+    ) -> Vector2f {
+        self.pull(bodies, boundary, position, None)
+    }
+}
+//charged bodies push apart if their charges have the same sign and pull together if not,
+//always as 1/r^2 whatever gravity does
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Coulomb {
+    pub strength: f32,
+}
+impl ForceModel for Coulomb {
+    fn accelerate(
+        &self,
+        bodies: &[SpaceBody],
+        boundary: &Boundary,
+        accelerations: &mut [Vector2f],
+    ) {
+        for (i, body) in bodies.iter().enumerate() {
+            if body.charge() == 0.0 {
+                continue;
+            }
+            for (j, other) in bodies.iter().enumerate() {
+                if i == j || other.charge() == 0.0 {
+                    continue;
+                }
+                let d = boundary.separation(other.pos2f(), body.pos2f());
+                let squared = d.x * d.x + d.y * d.y + COULOMB_SOFTENING;
+                let force = self.strength * body.charge() * other.charge() / squared.powf(1.5);
+                accelerations[i] += d * (force / body.mass());
+            }
+        }
+    }
+}
+//a spring between two bodies, found by their ids so it follows them as others come and go.
+//spring links pull with this
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spring {
+    pub a: u64,
+    pub b: u64,
+    pub stiffness: f32,
+    pub rest_length: f32,
+    pub damping: f32,
+}
+impl ForceModel for Spring {
+    fn accelerate(
+        &self,
+        bodies: &[SpaceBody],
+        boundary: &Boundary,
+        accelerations: &mut [Vector2f],
+    ) {
+        let find = |id| bodies.iter().position(|body| body.id() == id);
+        //a spring whose ends have merged or been removed does nothing
+        let (a, b) = match (find(self.a), find(self.b)) {
+            (Some(a), Some(b)) if a != b => (a, b),
+            _ => return,
+        };
+        let d = boundary.separation(bodies[a].pos2f(), bodies[b].pos2f());
+        let length = (d.x * d.x + d.y * d.y).sqrt();
+        if length <= 0.0 {
+            return;
+        }
+        let along = d / length;
+        let closing = bodies[b].velocity() - bodies[a].velocity();
+        let speed = closing.x * along.x + closing.y * along.y;
+        //positive pulls the ends together
+        let tension = self.stiffness * (length - self.rest_length) + self.damping * speed;
+        accelerations[a] += along * (tension / bodies[a].mass());
+        accelerations[b] -= along * (tension / bodies[b].mass());
+    }
+}
+//the same acceleration everywhere, like gravity near the ground
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct UniformField {
+    pub x: f32,
+    pub y: f32,
+}
+impl ForceModel for UniformField {
+    fn accelerate(
+        &self,
+        _bodies: &[SpaceBody],
+        _boundary: &Boundary,
+        accelerations: &mut [Vector2f],
+    ) {
+        for acceleration in accelerations.iter_mut() {
+            *acceleration += Vector2f::new(self.x, self.y);
+        }
+    }
+    fn accelerate_particle(
+        &self,
+        _bodies: &[SpaceBody],
+        _boundary: &Boundary,
+        _position: Vector2f,
+        _velocity: Vector2f,
+    ) -> Vector2f {
+        Vector2f::new(self.x, self.y)
+    }
+}
+//slows everything down in proportion to its speed, or to its speed squared when `quadratic`.
+//the coefficient is per unit of mass, so heavy and light bodies slow down alike
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Drag {
+    pub coefficient: f32,
+    #[serde(default)]
+    pub quadratic: bool,
+}
+impl Drag {
+    fn slow(&self, velocity: Vector2f) -> Vector2f {
+        let factor = if self.quadratic {
+            self.coefficient * (velocity.x * velocity.x + velocity.y * velocity.y).sqrt()
+        } else {
+            self.coefficient
+        };
+        -velocity * factor
+    }
+}
+impl ForceModel for Drag {
+    fn accelerate(
+        &self,
+        bodies: &[SpaceBody],
+        _boundary: &Boundary,
+        accelerations: &mut [Vector2f],
+    ) {
+        for (i, body) in bodies.iter().enumerate() {
+            accelerations[i] += self.slow(body.velocity());
+        }
+    }
+    fn accelerate_particle(
+        &self,
+        _bodies: &[SpaceBody],
+        _boundary: &Boundary,
+        _position: Vector2f,
+        velocity: Vector2f,
+    ) -> Vector2f {
+        self.slow(velocity)
+    }
+}
+//a force model as it is saved with the world. gravity takes its settings from the world so
+//the sliders keep working, the rest carry their own
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ForceSpec {
+    Gravity,
+    Coulomb(Coulomb),
+    UniformField(UniformField),
+    Drag(Drag),
+}
+impl ForceSpec {
+    pub fn model<'s>(&'s self, gravity: &'s Gravity) -> &'s dyn ForceModel {
+        match self {
+            ForceSpec::Gravity => gravity,
+            ForceSpec::Coulomb(model) => model,
+            ForceSpec::UniformField(model) => model,
+            ForceSpec::Drag(model) => model,
+        }
+    }
+    pub fn describe(&self) -> String {
+        match self {
+            ForceSpec::Gravity => "gravity".to_string(),
+            ForceSpec::Coulomb(c) => format!("coulomb, strength {}", c.strength),
+            ForceSpec::UniformField(f) => format!("uniform field ({}, {})", f.x, f.y),
+            ForceSpec::Drag(d) => format!(
+                "{} drag, coefficient {}",
+                if d.quadratic { "quadratic" } else { "linear" },
+                d.coefficient
+            ),
+        }
+    }
+}
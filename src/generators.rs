@@ -0,0 +1,359 @@
+use std::{f32::consts::PI, str::FromStr};
+
+use sfml::{graphics::Color, system::Vector2f};
+
+use crate::{
+    bodies::{ForceLaw, WorldSpace},
+    presets::{into_bodies, into_particles, Seed},
+};
+//every generator, with what it makes
+pub const GENERATORS: [(&str, &str); 4] = [
+    (
+        "disk",
+        "a disk of bodies on circular orbits around a central mass",
+    ),
+    (
+        "plummer",
+        "a round cluster whose speeds balance its own gravity",
+    ),
+    ("spiral", "a two armed spiral galaxy around a central bulge"),
+    (
+        "dust",
+        "bodies scattered evenly over a square, barely moving",
+    ),
+];
+//splitmix64, which is tiny and good enough for placing bodies, and gives the same
+//bodies for the same seed everywhere
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+    //uniform in [0, 1)
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+    //standard normal, from box-muller
+    pub fn normal(&mut self) -> f32 {
+        let u = 1.0 - self.next_f32();
+        let v = self.next_f32();
+        (-2.0 * u.ln()).sqrt() * (2.0 * PI * v).cos()
+    }
+    pub fn angle(&mut self) -> f32 {
+        self.range(0.0, 2.0 * PI)
+    }
+}
+//how a value like mass is picked for each body
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Distribution {
+    Fixed(f32),
+    Uniform { min: f32, max: f32 },
+    //chance of x goes as x^exponent, like the -2.35 of star masses
+    PowerLaw { min: f32, max: f32, exponent: f32 },
+}
+impl Distribution {
+    pub fn sample(&self, rng: &mut Rng) -> f32 {
+        match *self {
+            Distribution::Fixed(value) => value,
+            Distribution::Uniform { min, max } => rng.range(min, max),
+            Distribution::PowerLaw { min, max, exponent } => {
+                let u = rng.next_f32();
+                if (exponent + 1.0).abs() < 1e-6 {
+                    min * (max / min).powf(u)
+                } else {
+                    let a = exponent + 1.0;
+                    (min.powf(a) + u * (max.powf(a) - min.powf(a))).powf(1.0 / a)
+                }
+            }
+        }
+    }
+    fn min(&self) -> f32 {
+        match *self {
+            Distribution::Fixed(value) => value,
+            Distribution::Uniform { min, .. } | Distribution::PowerLaw { min, .. } => min,
+        }
+    }
+}
+//"3" is fixed, "1..3" is uniform and "1..100^-2.35" is a power law
+impl FromStr for Distribution {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |n: &str| match n.trim().parse::<f32>() {
+            Ok(value) if value.is_finite() => Ok(value),
+            _ => Err(format!("{} isn't a number", n)),
+        };
+        let distribution = match s.split_once("..") {
+            None => Distribution::Fixed(number(s)?),
+            Some((min, rest)) => match rest.split_once('^') {
+                None => Distribution::Uniform {
+                    min: number(min)?,
+                    max: number(rest)?,
+                },
+                Some((max, exponent)) => Distribution::PowerLaw {
+                    min: number(min)?,
+                    max: number(max)?,
+                    exponent: number(exponent)?,
+                },
+            },
+        };
+        match distribution {
+            Distribution::Fixed(value) if value <= 0.0 => {
+                Err(format!("{} has to be more than 0", s))
+            }
+            Distribution::Uniform { min, max } | Distribution::PowerLaw { min, max, .. }
+                if min <= 0.0 || max < min =>
+            {
+                Err(format!("{} needs 0 < min <= max", s))
+            }
+            _ => Ok(distribution),
+        }
+    }
+}
+#[derive(Debug, Clone)]
+pub struct GeneratorParams {
+    pub count: usize,
+    pub mass: Distribution,
+    pub radius: Distribution,
+    pub seed: u64,
+    //roughly how far from the middle bodies go
+    pub size: f32,
+    //mass of the star or bulge in the middle, for the generators that have one
+    pub central_mass: f32,
+    //whether everything but the middle is massless test particles
+    pub particles: bool,
+}
+impl Default for GeneratorParams {
+    fn default() -> Self {
+        GeneratorParams {
+            count: 300,
+            mass: Distribution::Uniform { min: 0.5, max: 2.0 },
+            radius: Distribution::Uniform { min: 2.0, max: 4.0 },
+            seed: 1,
+            size: 600.0,
+            central_mass: 400.0,
+            particles: false,
+        }
+    }
+}
+impl GeneratorParams {
+    //reads "count=500 seed=7 mass=1..3" style settings on top of the defaults
+    pub fn parse(settings: &[&str]) -> Result<GeneratorParams, String> {
+        let mut params = GeneratorParams::default();
+        for setting in settings {
+            let (key, value) = setting
+                .split_once('=')
+                .ok_or_else(|| format!("{} should look like name=value", setting))?;
+            let bad = || format!("{} isn't a valid {}", value, key);
+            let finite = || match value.parse::<f32>() {
+                Ok(number) if number.is_finite() => Ok(number),
+                _ => Err(bad()),
+            };
+            match key {
+                "count" => params.count = value.parse().map_err(|_| bad())?,
+                "seed" => params.seed = value.parse().map_err(|_| bad())?,
+                "size" => params.size = finite()?,
+                "central" => params.central_mass = finite()?,
+                "particles" => params.particles = value.parse().map_err(|_| bad())?,
+                "mass" => params.mass = value.parse()?,
+                "radius" => params.radius = value.parse()?,
+                _ => {
+                    return Err(format!(
+                        "unknown setting {}, the settings are count, seed, size, central, mass, radius and particles",
+                        key
+                    ))
+                }
+            }
+        }
+        if params.size <= params.radius.min() || params.central_mass <= 0.0 {
+            return Err(
+                "size has to be bigger than the bodies and central more than 0".to_string(),
+            );
+        }
+        Ok(params)
+    }
+}
+fn length(v: Vector2f) -> f32 {
+    (v.x * v.x + v.y * v.y).sqrt()
+}
+fn polar(radius: f32, angle: f32) -> Vector2f {
+    Vector2f::new(radius * angle.cos(), radius * angle.sin())
+}
+//test particles get no mass, so they orbit the middle as if nothing else were there
+fn body(rng: &mut Rng, params: &GeneratorParams, position: Vector2f, color: Color) -> Seed {
+    let mass = params.mass.sample(rng);
+    let mass = if params.particles { 0.0 } else { mass };
+    Seed::new(position, mass, params.radius.sample(rng), color)
+}
+fn central(params: &GeneratorParams, color: Color) -> Seed {
+    let radius = (params.central_mass.cbrt() * 3.0).max(params.radius.min() * 2.0);
+    Seed::new(Vector2f::new(0.0, 0.0), params.central_mass, radius, color)
+}
+//puts every body but the first on a circular orbit around the mass closer in than it,
+//which is exact for a round spread of mass with 1/r and close enough with 1/r^2
+fn circular_orbits(seeds: &mut [Seed], law: ForceLaw, gravity: f32) {
+    let mut order: Vec<usize> = (1..seeds.len()).collect();
+    order.sort_by(|&a, &b| length(seeds[a].position).total_cmp(&length(seeds[b].position)));
+    let mut enclosed = seeds[0].mass;
+    for i in order {
+        let offset = seeds[i].position;
+        let distance = length(offset).max(1.0);
+        let speed = law.circular_speed(gravity, enclosed, distance);
+        seeds[i].velocity = Vector2f::new(offset.y, -offset.x) * (speed / distance);
+        enclosed += seeds[i].mass;
+    }
+}
+//scales the velocities so kinetic energy balances the pull of gravity, 2K = sum of r.F,
+//after taking away any drift of the whole group
+fn virialize(seeds: &mut [Seed], law: ForceLaw, gravity: f32) {
+    let mass: f32 = seeds.iter().map(|s| s.mass).sum();
+    let drift = seeds
+        .iter()
+        .fold(Vector2f::new(0.0, 0.0), |sum, s| sum + s.velocity * s.mass)
+        / mass;
+    let mut kinetic = 0.0;
+    for seed in seeds.iter_mut() {
+        seed.velocity -= drift;
+        kinetic += 0.5 * seed.mass * (seed.velocity.x.powi(2) + seed.velocity.y.powi(2));
+    }
+    let mut virial = 0.0;
+    for i in 0..seeds.len() {
+        for j in i + 1..seeds.len() {
+            let distance = length(seeds[i].position - seeds[j].position).max(1.0);
+            virial += gravity * seeds[i].mass * seeds[j].mass * distance.powf(1.0 - law.exponent());
+        }
+    }
+    if kinetic > 0.0 {
+        let scale = (virial / (2.0 * kinetic)).sqrt();
+        for seed in seeds.iter_mut() {
+            seed.velocity *= scale;
+        }
+    }
+}
+fn disk(rng: &mut Rng, params: &GeneratorParams, law: ForceLaw, gravity: f32) -> Vec<Seed> {
+    let mut seeds = vec![central(params, Color::rgb(255, 220, 120))];
+    let inner = seeds[0].radius * 3.0;
+    for _ in 0..params.count {
+        //evenly spread over the area of the ring, so there are more bodies further out
+        let r = (rng.range(inner * inner, params.size * params.size)).sqrt();
+        let shade = (150.0 + 100.0 * (1.0 - r / params.size)) as u8;
+        let color = Color::rgb(shade, shade / 2 + 60, 80);
+        let position = polar(r, rng.angle());
+        seeds.push(body(rng, params, position, color));
+    }
+    circular_orbits(&mut seeds, law, gravity);
+    seeds
+}
+fn plummer(rng: &mut Rng, params: &GeneratorParams, law: ForceLaw, gravity: f32) -> Vec<Seed> {
+    //the size is where the spread is cut off, which is a few times the plummer radius
+    let scale = params.size / 4.0;
+    let mut seeds = Vec::with_capacity(params.count);
+    for _ in 0..params.count {
+        //a plummer sphere seen flat on, whose density goes as (1 + r^2/a^2)^-2
+        let u = rng.range(0.0, 0.94);
+        let r = scale * (u / (1.0 - u)).sqrt();
+        let color = Color::rgb(200, 210, 255);
+        let position = polar(r, rng.angle());
+        let mut seed = body(rng, params, position, color);
+        //bodies further out move slower, then everything is scaled at the end
+        let spread = (1.0 + r * r / (scale * scale)).powf(-0.25);
+        seed.velocity = Vector2f::new(rng.normal(), rng.normal()) * spread;
+        seeds.push(seed);
+    }
+    virialize(&mut seeds, law, gravity);
+    seeds
+}
+fn spiral(rng: &mut Rng, params: &GeneratorParams, law: ForceLaw, gravity: f32) -> Vec<Seed> {
+    let mut seeds = vec![central(params, Color::rgb(255, 240, 200))];
+    let inner = seeds[0].radius * 2.5;
+    //how tightly the arms wind, as the tangent of the pitch angle
+    let pitch = 0.3;
+    let falloff = params.size / 3.0;
+    for i in 0..params.count {
+        let arm = (i % 2) as f32 * PI;
+        //exponential disk, cut off at the size
+        let r = (inner - falloff * (1.0 - rng.next_f32() * 0.95).ln()).min(params.size);
+        let angle = arm + (r / inner).ln() / pitch + rng.normal() * 0.25;
+        let spread = rng.normal() * r * 0.05;
+        let position = polar(r + spread, angle);
+        let young = rng.next_f32() < 0.3;
+        let color = if young {
+            Color::rgb(150, 180, 255)
+        } else {
+            Color::rgb(255, 220, 180)
+        };
+        seeds.push(body(rng, params, position, color));
+    }
+    circular_orbits(&mut seeds, law, gravity);
+    seeds
+}
+fn dust(rng: &mut Rng, params: &GeneratorParams) -> Vec<Seed> {
+    (0..params.count)
+        .map(|_| {
+            let position = Vector2f::new(
+                rng.range(-params.size, params.size),
+                rng.range(-params.size, params.size),
+            );
+            let mut seed = body(rng, params, position, Color::rgb(170, 150, 130));
+            seed.velocity = Vector2f::new(rng.normal(), rng.normal()) * 0.5;
+            seed
+        })
+        .collect()
+}
+//replaces the bodies in `space` with ones made by the generator called `name`
+pub fn generate(
+    name: &str,
+    params: &GeneratorParams,
+    space: &mut WorldSpace,
+) -> Result<String, String> {
+    if params.particles && (name == "plummer" || name == "dust") {
+        return Err(format!(
+            "{} only holds together by its own pull, so it can't be made of particles",
+            name
+        ));
+    }
+    let mut rng = Rng::new(params.seed);
+    let law = space.force_law();
+    let gravity = space.gravity();
+    let seeds = match name {
+        "disk" => disk(&mut rng, params, law, gravity),
+        "plummer" => plummer(&mut rng, params, law, gravity),
+        "spiral" => spiral(&mut rng, params, law, gravity),
+        "dust" => dust(&mut rng, params),
+        _ => {
+            let names: Vec<&str> = GENERATORS.iter().map(|(name, _)| *name).collect();
+            return Err(format!(
+                "there is no generator called {}, the generators are {}",
+                name,
+                names.join(", ")
+            ));
+        }
+    };
+    let (particles, seeds): (Vec<Seed>, Vec<Seed>) = seeds
+        .into_iter()
+        .partition(|seed| params.particles && seed.mass == 0.0);
+    let bodies = into_bodies(seeds);
+    let count = bodies.len();
+    space.replace_bodies(bodies);
+    let particles = into_particles(particles);
+    let particle_count = particles.len();
+    for particle in particles {
+        space.push_particle(particle);
+    }
+    Ok(format!(
+        "generated {} with {} bodies and {} particles from seed {}",
+        name, count, particle_count, params.seed
+    ))
+}
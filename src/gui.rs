@@ -3,162 +3,696 @@ use std::{
     collections::BTreeSet,
     convert::TryInto,
     ops::{Deref, DerefMut},
+    time::{Duration, Instant},
 };
 
 use sfml::{
     graphics::{
-        CircleShape, Color, Drawable, Font, RenderTarget, RenderWindow, Shape, Text, Transformable,
+        CircleShape, Color, Drawable, Font, PrimitiveType, RectangleShape, RenderStates,
+        RenderTarget, RenderWindow, Shape, Text, Transformable, Vertex,
     },
     system::{SfBox, Vector2, Vector2f},
+    window::Event,
 };
 
 use crate::{
-    bodies::{SpaceBody, WorldSpace},
-    sliders::{ColorType, Slider},
-    widgets::Widget,
-    WINDOW_SIZE,
+    bodies::{SpaceBody, WorldSpace, MAX_SUBSTEPS, MAX_TICK_RATE, MIN_TICK_RATE, SAVE_PATH},
+    button::Button,
+    checkbox::Checkbox,
+    colorpicker::ColorPicker,
+    config::Config,
+    keybindings::{KeyAction, Keybindings},
+    layout::{Anchor, Item, Layout, Panel, Stack},
+    orbits::{self, Elements, OrbitLaunch, MAX_ECCENTRICITY},
+    particles::TestParticle,
+    presets::{self, PRESETS},
+    sliders::{Slider, SliderRange},
+    status::Status,
+    textbox::TextBox,
+    tides::{hill_radius, MAX_FRAGMENTS, MIN_FRAGMENTS},
+    widgets::{Binding, Command, EventResponse, Flag, Widget, WidgetAction, WidgetEvent},
+    PI,
 };
 const NEW_PLANET_SPEED_MOD: f32 = 2.5;
+const MASS_STEP: f32 = 5.0;
+pub const MIN_EXAMPLE_MASS: f32 = 1.0;
+pub const MAX_EXAMPLE_MASS: f32 = 10000.0;
+pub const MIN_EXAMPLE_RADIUS: f32 = 2.0;
+pub const MAX_EXAMPLE_RADIUS: f32 = 150.0;
+const MIN_POINT_COUNT: u32 = 20;
+const MAX_POINT_COUNT: u32 = 500;
+const EXAMPLE_MARGIN: f32 = 10.0;
+const SLIDER_DIMENSIONS: (f32, f32) = (255.0, 20.0);
+const TEXT_BOX_DIMENSIONS: (f32, f32) = (120.0, 28.0);
+const BUTTON_DIMENSIONS: (f32, f32) = (100.0, 36.0);
+const CHECKBOX_DIMENSIONS: (f32, f32) = (200.0, 26.0);
+const TOOLTIP_DELAY: Duration = Duration::from_millis(500);
+const TOOLTIP_TEXT_SIZE: u32 = 18;
+const HELP_TEXT_SIZE: u32 = 22;
+const PRESET_BUTTON_DIMENSIONS: (f32, f32) = (160.0, 36.0);
+const RECOLOR_BUTTON_DIMENSIONS: (f32, f32) = (234.0, 36.0);
+const ELEMENTS_TEXT_SIZE: u32 = 20;
+const CONIC_POINTS: usize = 256;
+pub const SPAWN_PANEL: &str = "Spawn";
+pub const SIMULATION_PANEL: &str = "Simulation";
+pub const CONTROLS_PANEL: &str = "Controls";
+pub const PRESETS_PANEL: &str = "Presets";
+pub const STATUS_PANEL: &str = "Status";
+pub const PANEL_TITLES: [&str; 5] = [
+    SPAWN_PANEL,
+    SIMULATION_PANEL,
+    CONTROLS_PANEL,
+    PRESETS_PANEL,
+    STATUS_PANEL,
+];
+//the planet shown at the bottom of the window which new bodies are copied from
+#[derive(Debug)]
+pub struct ExamplePlanet<'a> {
+    pub shape: CircleShape<'a>,
+    mass: f32,
+    radius: f32,
+    immovable: bool,
+    orbit_spawn: bool,
+    orbit: OrbitLaunch,
+    particle: bool,
+    window_size: Vector2f,
+}
+impl<'a> ExamplePlanet<'a> {
+    fn new(mass: f32, radius: f32, window_size: Vector2f) -> ExamplePlanet<'a> {
+        let mut example = ExamplePlanet {
+            shape: CircleShape::new(radius, 100),
+            mass,
+            radius,
+            immovable: false,
+            orbit_spawn: false,
+            orbit: OrbitLaunch::default(),
+            particle: false,
+            window_size,
+        };
+        example.set_radius(radius);
+        example
+    }
+    pub fn mass(&self) -> f32 {
+        self.mass
+    }
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+    //whether bodies spawned from it stay where they are put
+    pub fn immovable(&self) -> bool {
+        self.immovable
+    }
+    pub fn set_immovable(&mut self, immovable: bool) {
+        self.immovable = immovable;
+    }
+    //whether clicking puts bodies straight into orbit instead of dragging to launch them
+    pub fn orbit_spawn(&self) -> bool {
+        self.orbit_spawn
+    }
+    pub fn set_orbit_spawn(&mut self, orbit_spawn: bool) {
+        self.orbit_spawn = orbit_spawn;
+    }
+    //whether new bodies are massless test particles
+    pub fn particle(&self) -> bool {
+        self.particle
+    }
+    pub fn set_particle(&mut self, particle: bool) {
+        self.particle = particle;
+    }
+    pub fn orbit(&self) -> &OrbitLaunch {
+        &self.orbit
+    }
+    pub fn orbit_mut(&mut self) -> &mut OrbitLaunch {
+        &mut self.orbit
+    }
+    fn set_window_size(&mut self, window_size: Vector2f) {
+        self.window_size = window_size;
+        self.set_radius(self.radius);
+    }
+    //returns false and leaves the mass alone if it isn't a positive number
+    pub fn set_mass(&mut self, mass: f32) -> bool {
+        if !mass.is_finite() || mass <= 0.0 {
+            return false;
+        }
+        self.mass = mass;
+        true
+    }
+    //returns false and leaves the radius alone if it isn't a positive number
+    pub fn set_radius(&mut self, radius: f32) -> bool {
+        if !radius.is_finite() || radius <= 0.0 {
+            return false;
+        }
+        self.radius = radius;
+        self.shape.set_radius(radius);
+        self.shape.set_point_count(point_count(radius));
+        self.shape.set_position((
+            self.window_size.x / 2.0 - radius,
+            self.window_size.y - 2.0 * radius - EXAMPLE_MARGIN,
+        ));
+        true
+    }
+}
 #[derive(Debug)]
 pub struct Gui<'a> {
-    example_planet: CircleShape<'a>,
+    example: ExamplePlanet<'a>,
     held_position: Option<Vector2f>,
-    radius: f32,
     size: Vector2<u32>,
     font: &'a SfBox<Font>,
-    mass: f32,
-    text: Option<Text<'a>>,
     trail_line: Option<[GuideLinePoint<'a>; 10]>,
     focused_planet: Option<CircleShape<'a>>,
     focused_number_display: Option<Text<'a>>,
     pub widgets: BTreeSet<RefCell<Box<dyn Widget>>>,
     click_held: Option<usize>,
+    keyboard_focus: Option<usize>,
+    hovered: Option<usize>,
+    hovered_since: Option<Instant>,
+    layout: Layout,
+    help_lines: Vec<String>,
+    show_help: bool,
 }
 
 impl<'a> Gui<'a> {
-    pub fn new(size: Vector2<u32>, font: &'a SfBox<Font>) -> Gui<'a> {
-        let mut circle = CircleShape::new(30.0, 100);
-        let default_radius = 30;
-        circle.set_position((
-            (default_radius) as f32,
-            (size.y - 2 * default_radius) as f32,
-        ));
+    pub fn new(
+        size: Vector2<u32>,
+        font: &'a SfBox<Font>,
+        space: &WorldSpace,
+        config: &Config,
+        keybindings: &Keybindings,
+    ) -> Gui<'a> {
+        let spawn = &config.spawn;
+        let mut example = ExamplePlanet::new(
+            spawn.mass,
+            spawn.radius,
+            Vector2f::new(size.x as f32, size.y as f32),
+        );
+        let (r, g, b) = spawn.color_rgb;
+        example.shape.set_fill_color(Color::rgb(r, g, b));
+        example.set_immovable(spawn.immovable);
         #[allow(clippy::mutable_key_type)]
         let mut set = BTreeSet::new();
-        set.insert(RefCell::new(Box::new(Slider::new(
-            ColorType::Blue,
+        let mut spawn_items = Vec::new();
+        spawn_items.push(Item::Widget(set.len()));
+        set.insert(RefCell::new(
+            Box::new(ColorPicker::new(set.len())) as Box<dyn Widget>
+        ));
+        spawn_items.push(Item::Widget(set.len()));
+        set.insert(RefCell::new(Box::new(Button::new(
+            "recolour selected",
+            Command::RecolorSelected,
             set.len(),
-            (10.0, 1400.0),
-            (255.0, 20.0),
+            RECOLOR_BUTTON_DIMENSIONS,
+        )) as Box<dyn Widget>));
+        let mass_slider = set.len();
+        set.insert(RefCell::new(Box::new(Slider::bound(
+            Binding::Mass,
+            SliderRange::log(MIN_EXAMPLE_MASS, MAX_EXAMPLE_MASS, example.mass()),
+            set.len(),
+            (0.0, 0.0),
+            SLIDER_DIMENSIONS,
             5.0,
         )) as Box<dyn Widget>));
-        set.insert(RefCell::new(Box::new(Slider::new(
-            ColorType::Green,
+        let mass_box = set.len();
+        set.insert(RefCell::new(Box::new(TextBox::new(
+            Binding::Mass,
+            set.len(),
+            (0.0, 0.0),
+            TEXT_BOX_DIMENSIONS,
+        )) as Box<dyn Widget>));
+        spawn_items.push(Item::Stack(Stack::row(vec![
+            Item::Widget(mass_slider),
+            Item::Widget(mass_box),
+        ])));
+        let radius_slider = set.len();
+        set.insert(RefCell::new(Box::new(Slider::bound(
+            Binding::Radius,
+            SliderRange::linear(MIN_EXAMPLE_RADIUS, MAX_EXAMPLE_RADIUS, example.radius()),
             set.len(),
-            (10.0, 1300.0),
-            (255.0, 20.0),
+            (0.0, 0.0),
+            SLIDER_DIMENSIONS,
             5.0,
         )) as Box<dyn Widget>));
-        set.insert(RefCell::new(Box::new(Slider::new(
-            ColorType::Red,
+        let radius_box = set.len();
+        set.insert(RefCell::new(Box::new(TextBox::new(
+            Binding::Radius,
             set.len(),
-            (10.0, 1200.0),
-            (255.0, 20.0),
+            (0.0, 0.0),
+            TEXT_BOX_DIMENSIONS,
+        )) as Box<dyn Widget>));
+        spawn_items.push(Item::Stack(Stack::row(vec![
+            Item::Widget(radius_slider),
+            Item::Widget(radius_box),
+        ])));
+        spawn_items.push(Item::Widget(set.len()));
+        set.insert(RefCell::new(Box::new(Checkbox::new(
+            Flag::OrbitSpawn,
+            set.len(),
+            CHECKBOX_DIMENSIONS,
+        )) as Box<dyn Widget>));
+        let eccentricity_slider = set.len();
+        set.insert(RefCell::new(Box::new(Slider::bound(
+            Binding::Eccentricity,
+            SliderRange::linear(0.0, MAX_ECCENTRICITY, example.orbit().eccentricity),
+            set.len(),
+            (0.0, 0.0),
+            SLIDER_DIMENSIONS,
             5.0,
         )) as Box<dyn Widget>));
+        let eccentricity_box = set.len();
+        set.insert(RefCell::new(Box::new(TextBox::new(
+            Binding::Eccentricity,
+            set.len(),
+            (0.0, 0.0),
+            TEXT_BOX_DIMENSIONS,
+        )) as Box<dyn Widget>));
+        spawn_items.push(Item::Stack(Stack::row(vec![
+            Item::Widget(eccentricity_slider),
+            Item::Widget(eccentricity_box),
+        ])));
+        let mut orbit_flags = Vec::new();
+        for flag in [Flag::Clockwise, Flag::OrbitSelected].iter() {
+            orbit_flags.push(Item::Widget(set.len()));
+            set.insert(RefCell::new(
+                Box::new(Checkbox::new(*flag, set.len(), CHECKBOX_DIMENSIONS)) as Box<dyn Widget>,
+            ));
+        }
+        spawn_items.push(Item::Stack(Stack::row(orbit_flags)));
+        let simulation_sliders = vec![
+            (
+                Binding::Gravity,
+                SliderRange::log(1.0, 1000.0, space.gravity()),
+            ),
+            (Binding::Dt, SliderRange::log(0.001, 1.0, space.dt())),
+            (
+                Binding::TrailLength,
+                SliderRange::linear(0.0, 500.0, space.trail_length() as f32).with_step(1.0),
+            ),
+            (Binding::Zoom, SliderRange::log(0.1, 10.0, space.zoom())),
+            (
+                Binding::Substeps,
+                SliderRange::linear(1.0, MAX_SUBSTEPS as f32, space.substeps() as f32)
+                    .with_step(1.0),
+            ),
+            (
+                Binding::TickRate,
+                SliderRange::log(MIN_TICK_RATE, MAX_TICK_RATE, space.tick_rate()).with_step(1.0),
+            ),
+            (
+                Binding::Fragments,
+                SliderRange::linear(
+                    MIN_FRAGMENTS as f32,
+                    MAX_FRAGMENTS as f32,
+                    space.fragment_count() as f32,
+                )
+                .with_step(1.0),
+            ),
+            (
+                Binding::EscapeRadius,
+                SliderRange::log(100.0, 100000.0, space.escape_radius()),
+            ),
+        ];
+        let mut simulation_items = Vec::new();
+        for (binding, range) in simulation_sliders {
+            simulation_items.push(Item::Widget(set.len()));
+            set.insert(RefCell::new(Box::new(Slider::bound(
+                binding,
+                range,
+                set.len(),
+                (0.0, 0.0),
+                SLIDER_DIMENSIONS,
+                5.0,
+            )) as Box<dyn Widget>));
+        }
+        let mut control_buttons = Vec::new();
+        let buttons = vec![
+            Button::new("pause", Command::TogglePause, set.len(), BUTTON_DIMENSIONS)
+                .with_alt_label(Flag::Paused, "resume"),
+            Button::new(
+                "clear",
+                Command::ClearBodies,
+                set.len() + 1,
+                BUTTON_DIMENSIONS,
+            )
+            .needs_confirmation(),
+            Button::new("save", Command::Save, set.len() + 2, BUTTON_DIMENSIONS),
+        ];
+        for button in buttons {
+            control_buttons.push(Item::Widget(set.len()));
+            set.insert(RefCell::new(Box::new(button) as Box<dyn Widget>));
+        }
+        let mut control_items = vec![Item::Stack(Stack::row(control_buttons))];
+        for flag in [
+            Flag::Trails,
+            Flag::ImmovableSpawn,
+            Flag::ParticleSpawn,
+            Flag::AbsorbParticles,
+            Flag::TidalDisruption,
+            Flag::Fragmentation,
+            Flag::HillSphere,
+        ]
+        .iter()
+        {
+            control_items.push(Item::Widget(set.len()));
+            set.insert(RefCell::new(
+                Box::new(Checkbox::new(*flag, set.len(), CHECKBOX_DIMENSIONS)) as Box<dyn Widget>,
+            ));
+        }
+        let mut preset_items = Vec::new();
+        for (name, _) in PRESETS.iter() {
+            preset_items.push(Item::Widget(set.len()));
+            set.insert(RefCell::new(Box::new(Button::new(
+                name,
+                Command::LoadPreset(name),
+                set.len(),
+                PRESET_BUTTON_DIMENSIONS,
+            )) as Box<dyn Widget>));
+        }
+        let help_hint = keybindings
+            .keys_for(KeyAction::ToggleHelp)
+            .first()
+            .map(|key| format!("{} for help", key));
+        let status_items = vec![Item::Widget(set.len())];
+        set.insert(RefCell::new(
+            Box::new(Status::new(help_hint, set.len())) as Box<dyn Widget>
+        ));
+        let mut presets_panel =
+            Panel::new(PRESETS_PANEL, Anchor::Left, Stack::column(preset_items));
+        presets_panel.collapsed = true;
+        let mut layout = Layout::new(vec![
+            Panel::new(SPAWN_PANEL, Anchor::BottomLeft, Stack::column(spawn_items)),
+            Panel::new(
+                SIMULATION_PANEL,
+                Anchor::BottomRight,
+                Stack::column(simulation_items),
+            ),
+            Panel::new(CONTROLS_PANEL, Anchor::Top, Stack::column(control_items)),
+            presets_panel,
+            Panel::new(STATUS_PANEL, Anchor::TopLeft, Stack::column(status_items)),
+        ]);
+        //the config only changes panels it names, which it has already checked exist
+        for panel_config in &config.panels {
+            if let Some(panel) = layout
+                .panels
+                .iter_mut()
+                .find(|p| p.title == panel_config.title)
+            {
+                panel.anchor = panel_config.anchor.unwrap_or(panel.anchor);
+                panel.collapsed = panel_config.collapsed.unwrap_or(panel.collapsed);
+                panel.margin = panel_config.margin.unwrap_or(panel.margin);
+                panel.padding = panel_config.padding.unwrap_or(panel.padding);
+            }
+        }
+        layout.arrange(size, &set);
 
         Gui {
-            example_planet: circle,
+            example,
             held_position: None,
-            radius: 30.0,
             size,
             font,
-            mass: 30.0,
-            text: None,
             trail_line: None,
             focused_planet: None,
             focused_number_display: None,
             widgets: set,
             click_held: None,
+            keyboard_focus: None,
+            hovered: None,
+            hovered_since: None,
+            layout,
+            help_lines: keybindings.help_lines(),
+            show_help: false,
         }
     }
-    pub fn mouse_moved(&mut self, x: i32, y: i32) {
-        if let Some(wid) = self.click_held {
-            self.widgets
-                .iter()
-                .nth(wid)
-                .unwrap()
-                .borrow_mut()
-                .mouse_moved(&mut self.example_planet, x, y);
+    fn widget(&self, layer: usize) -> &RefCell<Box<dyn Widget>> {
+        self.widgets.iter().nth(layer).unwrap()
+    }
+    fn widget_at(&self, x: i32, y: i32) -> Option<usize> {
+        self.widgets
+            .iter()
+            .filter(|w| !self.layout.is_hidden(w.borrow().get_layer()))
+            .find(|w| inside(w.borrow().get_bounds(), Vector2::new(x, y)))
+            .map(|w| w.borrow().get_layer())
+    }
+    //lays the widgets out again for a window of the new size
+    pub fn resize(&mut self, size: Vector2<u32>) {
+        self.size = size;
+        self.example
+            .set_window_size(Vector2f::new(size.x as f32, size.y as f32));
+        self.layout.arrange(size, &self.widgets);
+    }
+    //passes the event to whichever widget it is for, returning true if a widget used it
+    pub fn handle_event(&mut self, event: &Event, space: &mut WorldSpace) -> bool {
+        let widget_event = match WidgetEvent::from_sfml(event) {
+            Some(e) => e,
+            None => return false,
+        };
+        let target = match widget_event {
+            WidgetEvent::MouseDown { x, y }
+                if self
+                    .layout
+                    .toggle_at(Vector2f::new(x as f32, y as f32), &self.widgets) =>
+            {
+                if let Some(layer) = self.keyboard_focus {
+                    if self.layout.is_hidden(layer) {
+                        self.set_keyboard_focus(None, space);
+                    }
+                }
+                return true;
+            }
+            WidgetEvent::MouseDown { x, y } => {
+                let under = self.widget_at(x, y);
+                let focus = under.filter(|&l| self.widget(l).borrow().accepts_focus());
+                self.set_keyboard_focus(focus, space);
+                self.click_held = under;
+                under
+            }
+            WidgetEvent::MouseUp { .. } => self.click_held.take(),
+            WidgetEvent::MouseMove { x, y } => {
+                self.update_hover(self.widget_at(x, y), space);
+                self.click_held
+            }
+            WidgetEvent::Wheel { x, y, .. } => self.widget_at(x, y),
+            WidgetEvent::Key { .. } | WidgetEvent::Text(_) => self.keyboard_focus,
+            WidgetEvent::FocusGained
+            | WidgetEvent::FocusLost
+            | WidgetEvent::MouseEnter
+            | WidgetEvent::MouseLeave => None,
+        };
+        match target {
+            Some(layer) => self.send_event(layer, &widget_event, space),
+            None => false,
         }
     }
-    pub fn update_draw(&mut self, target: &mut RenderWindow) {
-        if self.held_position.is_none() {
-            self.example_planet.draw(target, Default::default());
+    fn send_event(&mut self, layer: usize, event: &WidgetEvent, space: &mut WorldSpace) -> bool {
+        let response = self.widget(layer).borrow_mut().handle_event(event);
+        match response {
+            EventResponse::Ignored => false,
+            EventResponse::Consumed(actions) => {
+                self.apply_actions(actions, space);
+                true
+            }
+        }
+    }
+    fn apply_actions(&mut self, actions: Vec<WidgetAction>, space: &mut WorldSpace) {
+        for action in actions {
+            match action {
+                WidgetAction::Set(binding, value) => binding.set(value, &mut self.example, space),
+                WidgetAction::SetColor(color) => self.example.shape.set_fill_color(color),
+                WidgetAction::SetFlag(flag, value) => flag.set(value, &mut self.example, space),
+                WidgetAction::Run(Command::TogglePause) => space.switch_stopped(),
+                WidgetAction::Run(Command::ClearBodies) => space.clear_bodies(),
+                WidgetAction::Run(Command::RecolorSelected) => {
+                    space.recolor_selected(self.example.shape.fill_color())
+                }
+                WidgetAction::Run(Command::LoadPreset(name)) => {
+                    if let Err(e) = presets::apply(name, space) {
+                        eprintln!("{}", e);
+                    }
+                }
+                WidgetAction::Run(Command::Save) => {
+                    if let Err(e) = space.serialize(SAVE_PATH) {
+                        eprintln!("Couldn't save to {}: {}", SAVE_PATH, e);
+                    }
+                }
+                WidgetAction::ReleaseFocus => self.set_keyboard_focus(None, space),
+            }
+        }
+    }
+    //tells the widgets the mouse moved between that it left one and entered the other
+    fn update_hover(&mut self, layer: Option<usize>, space: &mut WorldSpace) {
+        if layer == self.hovered {
+            return;
+        }
+        if let Some(old) = self.hovered.take() {
+            self.send_event(old, &WidgetEvent::MouseLeave, space);
+        }
+        self.hovered_since = layer.map(|_| Instant::now());
+        if let Some(new) = layer {
+            self.hovered = Some(new);
+            self.send_event(new, &WidgetEvent::MouseEnter, space);
+        }
+    }
+    fn set_keyboard_focus(&mut self, layer: Option<usize>, space: &mut WorldSpace) {
+        if layer == self.keyboard_focus {
+            return;
+        }
+        if let Some(old) = self.keyboard_focus.take() {
+            self.send_event(old, &WidgetEvent::FocusLost, space);
+        }
+        if let Some(new) = layer {
+            self.keyboard_focus = Some(new);
+            self.send_event(new, &WidgetEvent::FocusGained, space);
         }
-        if self.text.is_none() {
-            self.text = Some(Text::new(&self.mass.to_string(), &self.font, 30));
+    }
+    pub fn example(&self) -> &ExamplePlanet<'a> {
+        &self.example
+    }
+    //returns false and changes nothing if `value` doesn't make sense for `binding`
+    pub fn set_binding(&mut self, binding: Binding, value: f32, space: &mut WorldSpace) -> bool {
+        if !binding.accepts(value) {
+            return false;
         }
-        if self.text.as_ref().unwrap().string().to_rust_string() != self.mass.to_string() {
-            self.text
-                .as_mut()
-                .unwrap()
-                .set_string(&self.mass.to_string());
+        binding.set(value, &mut self.example, space);
+        true
+    }
+    //gives the keyboard to the text box for `binding`, if there is one
+    pub fn focus_text_box(&mut self, binding: Binding, space: &mut WorldSpace) {
+        let layer = self
+            .widgets
+            .iter()
+            .find(|w| w.borrow().accepts_focus() && w.borrow().binding() == Some(binding))
+            .map(|w| w.borrow().get_layer());
+        self.set_keyboard_focus(layer, space);
+    }
+    fn sync_widgets(&self, space: &WorldSpace) {
+        for widget in &self.widgets {
+            widget.borrow_mut().sync(&self.example, space);
+        }
+    }
+    pub fn update_draw(&mut self, target: &mut RenderWindow, space: &WorldSpace) {
+        self.sync_widgets(space);
+        if self.held_position.is_none() {
+            self.example.shape.draw(target, Default::default());
         }
-        self.text.as_ref().unwrap().draw(target, Default::default());
         assert_eq!(self.held_position.is_none(), self.trail_line.is_none());
         if self.held_position.is_some() {
             self.update_guideline(target.mouse_position());
             self.draw_guideline(target);
+        } else if self.example.orbit_spawn() {
+            self.draw_orbit_guide(target, space);
         }
         self.draw_widgets(target);
+        self.draw_tooltip(target);
+        if self.show_help {
+            self.draw_help(target);
+        }
+    }
+    pub fn toggle_help(&mut self) {
+        self.show_help = !self.show_help;
+    }
+    pub fn toggle_orbit_spawn(&mut self) {
+        let orbit_spawn = self.example.orbit_spawn();
+        self.example.set_orbit_spawn(!orbit_spawn);
+    }
+    fn draw_tooltip(&self, target: &mut RenderWindow) {
+        let layer = match self.hovered {
+            Some(layer) if self.click_held.is_none() && !self.layout.is_hidden(layer) => layer,
+            _ => return,
+        };
+        match self.hovered_since {
+            Some(since) if since.elapsed() >= TOOLTIP_DELAY => {}
+            _ => return,
+        }
+        let tip = match self.widget(layer).borrow().tooltip() {
+            Some(tip) => tip,
+            None => return,
+        };
+        let mut text = Text::new(&tip, self.font, TOOLTIP_TEXT_SIZE);
+        let bounds = text.local_bounds();
+        let size = Vector2f::new(bounds.width + 12.0, bounds.top + bounds.height + 10.0);
+        let mouse = target.mouse_position();
+        //kept inside the window, so tooltips near the right edge go to the left of the mouse
+        let position = Vector2f::new(
+            (mouse.x as f32 + 16.0)
+                .min(self.size.x as f32 - size.x)
+                .max(0.0),
+            (mouse.y as f32 + 20.0)
+                .min(self.size.y as f32 - size.y)
+                .max(0.0),
+        );
+        let mut background = RectangleShape::with_size(size);
+        background.set_position(position);
+        background.set_fill_color(Color::rgba(20, 20, 20, 230));
+        background.set_outline_thickness(1.0);
+        background.set_outline_color(Color::rgb(120, 120, 120));
+        target.draw(&background);
+        text.set_position(position + Vector2f::new(6.0, 4.0));
+        target.draw(&text);
+    }
+    fn draw_help(&self, target: &mut dyn RenderTarget) {
+        let mut text = Text::new(
+            &format!("Controls\n\n{}", self.help_lines.join("\n")),
+            self.font,
+            HELP_TEXT_SIZE,
+        );
+        let bounds = text.local_bounds();
+        let size = Vector2f::new(bounds.width + 40.0, bounds.top + bounds.height + 40.0);
+        let position = Vector2f::new(
+            ((self.size.x as f32 - size.x) / 2.0).max(0.0),
+            ((self.size.y as f32 - size.y) / 2.0).max(0.0),
+        );
+        let mut background = RectangleShape::with_size(size);
+        background.set_position(position);
+        background.set_fill_color(Color::rgba(10, 10, 20, 220));
+        background.set_outline_thickness(2.0);
+        background.set_outline_color(Color::rgb(120, 120, 140));
+        target.draw(&background);
+        text.set_position(position + Vector2f::new(20.0, 20.0));
+        target.draw(&text);
     }
     fn draw_widgets(&self, target: &mut dyn RenderTarget) {
+        self.layout.draw(target, self.font);
         for widget in &self.widgets {
-            widget.borrow().draw(target);
+            if !self.layout.is_hidden(widget.borrow().get_layer()) {
+                widget.borrow().draw(target, self.font);
+            }
         }
     }
+    //clicks that no widget used go here, and launch new planets
     pub fn click(&mut self, space: &mut WorldSpace, mouse_pos: Vector2<i32>) {
-        for widget in self.widgets.iter() {
-            let bounds = widget.borrow().get_bounds();
-            if inside(bounds, mouse_pos) {
-                widget.borrow_mut().click(self, space);
-                self.click_held = Some(widget.borrow().get_layer());
-                return;
-            }
-        }
-        let adj_pos_x = mouse_pos.x as f32;
-        let adj_pos_y = mouse_pos.y as f32;
-        if self.held_position.is_some() {
-            space.push_body(SpaceBody::new(
-                (
-                    adj_pos_x + space.cam_pos.x - WINDOW_SIZE.0 / 2.0,
-                    adj_pos_y + space.cam_pos.y - WINDOW_SIZE.1 / 2.0,
-                ),
-                self.mass,
-                self.radius,
-                (mouse_pos.x as f32 - self.held_position.unwrap().x) / NEW_PLANET_SPEED_MOD,
-                (mouse_pos.y as f32 - self.held_position.unwrap().y) / NEW_PLANET_SPEED_MOD,
-                false,
-                self.example_planet.fill_color(),
-                space.bodies.len(),
-            ));
+        let mouse = Vector2f::new(mouse_pos.x as f32, mouse_pos.y as f32);
+        if self.example.orbit_spawn() && self.held_position.is_none() {
+            let position = space.screen_to_world(mouse);
+            //with nothing to orbit the body is just left where it is
+            let velocity = orbits::launch_velocity(space, position, self.example.orbit())
+                .unwrap_or_else(|| Vector2f::new(0.0, 0.0));
+            self.spawn(space, position, velocity);
+        } else if let Some(held) = self.held_position {
+            let position = space.screen_to_world(mouse);
+            let velocity = (mouse - held) / NEW_PLANET_SPEED_MOD / space.zoom();
+            self.spawn(space, position, velocity);
             self.held_position = None;
             self.trail_line = None;
         } else {
-            self.held_position = Some(Vector2f::new(adj_pos_x, adj_pos_y));
+            self.held_position = Some(mouse);
             self.update_guideline(mouse_pos);
         }
     }
-    pub fn release_click(&mut self, space: &mut WorldSpace) {
-        self.click_held = None;
-        for widget in self.widgets.iter() {
-            if widget.borrow().is_click_held() {
-                widget
-                    .borrow_mut()
-                    .release_click(&mut self.example_planet, space);
-            }
+    //adds a copy of the example planet, or a test particle in its colour
+    fn spawn(&self, space: &mut WorldSpace, position: Vector2f, velocity: Vector2f) {
+        let color = self.example.shape.fill_color();
+        if self.example.particle() {
+            space.push_particle(TestParticle::new(position, velocity, color));
+            return;
         }
+        space.push_body(SpaceBody::new(
+            (position.x, position.y),
+            self.example.mass(),
+            self.example.radius(),
+            velocity.x,
+            velocity.y,
+            self.example.immovable(),
+            color,
+            space.bodies.len(),
+        ));
     }
     fn update_guideline(&mut self, mouse_pos: Vector2<i32>) {
         let adj_pos_x = mouse_pos.x as f32;
@@ -183,6 +717,23 @@ impl<'a> Gui<'a> {
                 .unwrap(),
         );
     }
+    //a line from the mouse to the body a click would put a new body in orbit around
+    fn draw_orbit_guide(&self, target: &mut RenderWindow, space: &WorldSpace) {
+        let mouse = target.mouse_position();
+        let mouse = Vector2f::new(mouse.x as f32, mouse.y as f32);
+        let position = space.screen_to_world(mouse);
+        let index = match orbits::attractor(space, position, self.example.orbit().target) {
+            Some(index) => index,
+            None => return,
+        };
+        let center = space.world_to_screen(space.bodies[index].pos2f());
+        let color = Color::rgba(120, 125, 129, 160);
+        let line = [
+            Vertex::with_pos_color(mouse, color),
+            Vertex::with_pos_color(center, color),
+        ];
+        target.draw_primitives(&line, PrimitiveType::Lines, RenderStates::default());
+    }
     fn draw_guideline(&self, target: &mut dyn RenderTarget) {
         for i in self.trail_line.as_ref().unwrap().iter() {
             i.draw(target, Default::default());
@@ -191,37 +742,100 @@ impl<'a> Gui<'a> {
     pub fn update_draw_focused_display(
         &mut self,
         opt: Option<(CircleShape<'a>, usize)>,
+        space: &WorldSpace,
         target: &mut dyn RenderTarget,
     ) {
         if let Some(pair) = opt {
             let mut shape = pair.0;
             let index = pair.1;
-            shape.set_position((WINDOW_SIZE.0 - shape.radius() * 2.0 - 30.0, 0.0));
+            let width = self.size.x as f32;
+            shape.set_position((width - shape.radius() * 2.0 - 30.0, 0.0));
             let mut text = Text::new(&format!("#{}", index + 1), self.font, 50);
-            text.set_position((
-                WINDOW_SIZE.0 - 3.0 * shape.radius(),
-                shape.radius() * 2.0 + 30.0,
-            ));
+            text.set_position((width - 3.0 * shape.radius(), shape.radius() * 2.0 + 30.0));
             self.focused_number_display = Some(text);
             self.focused_planet = Some(shape);
             target.draw(self.focused_number_display.as_ref().unwrap());
             target.draw(self.focused_planet.as_ref().unwrap());
+            let mut info = format!("spin: {:.3}", space.bodies[index].spin());
+            if let Some(elements) = orbits::elements(space, index) {
+                self.draw_orbit(target, space, &elements);
+                if space.show_hill_sphere() {
+                    self.draw_hill_sphere(target, space, index, &elements);
+                }
+                info.push('\n');
+                info.push_str(&format_elements(&elements));
+            }
+            let mut text = Text::new(&info, self.font, ELEMENTS_TEXT_SIZE);
+            let radius = self.focused_planet.as_ref().unwrap().radius();
+            text.set_position((width - 330.0, radius * 2.0 + 100.0));
+            target.draw(&text);
         } else {
             self.focused_planet = None;
             self.focused_number_display = {
                 let mut text = Text::new("No planet selected.", self.font, 30);
-                text.set_position((WINDOW_SIZE.0 - 350.0, 0.0));
+                text.set_position((self.size.x as f32 - 350.0, 0.0));
                 text.set_fill_color(Color::WHITE);
                 Some(text)
             };
             target.draw(self.focused_number_display.as_ref().unwrap());
         }
     }
+    //the osculating conic, or under 1/r the circles the body stays between
+    fn draw_orbit(&self, target: &mut dyn RenderTarget, space: &WorldSpace, elements: &Elements) {
+        let color = Color::rgba(140, 180, 230, 150);
+        if elements.conic.is_some() {
+            //far enough that open orbits run off the screen
+            let reach = (self.size.x + self.size.y) as f32 / space.zoom();
+            let line: Vec<Vertex> = elements
+                .conic_points(CONIC_POINTS, reach)
+                .into_iter()
+                .map(|p| Vertex::with_pos_color(space.world_to_screen(p), color))
+                .collect();
+            target.draw_primitives(&line, PrimitiveType::LineStrip, RenderStates::default());
+            return;
+        }
+        let focus = space.world_to_screen(elements.focus);
+        for distance in [Some(elements.periapsis), elements.apoapsis]
+            .iter()
+            .flatten()
+        {
+            let radius = distance * space.zoom();
+            let mut circle = CircleShape::new(radius, point_count(radius));
+            circle.set_position(focus - Vector2f::new(radius, radius));
+            circle.set_fill_color(Color::TRANSPARENT);
+            circle.set_outline_thickness(1.0);
+            circle.set_outline_color(color);
+            target.draw(&circle);
+        }
+    }
+    fn draw_hill_sphere(
+        &self,
+        target: &mut dyn RenderTarget,
+        space: &WorldSpace,
+        index: usize,
+        elements: &Elements,
+    ) {
+        let body = &space.bodies[index];
+        let attractor = &space.bodies[elements.attractor];
+        let radius = hill_radius(elements, body.mass(), attractor.mass()) * space.zoom();
+        if !radius.is_finite() {
+            return;
+        }
+        let center = space.world_to_screen(body.pos2f());
+        let mut circle = CircleShape::new(radius, point_count(radius));
+        circle.set_position(center - Vector2f::new(radius, radius));
+        circle.set_fill_color(Color::rgba(120, 200, 140, 25));
+        circle.set_outline_thickness(1.0);
+        circle.set_outline_color(Color::rgba(120, 200, 140, 160));
+        target.draw(&circle);
+    }
     pub fn increase_example_mass(&mut self) {
-        self.mass += 5.0;
+        let mass = (self.example.mass() + MASS_STEP).clamp(MIN_EXAMPLE_MASS, MAX_EXAMPLE_MASS);
+        self.example.set_mass(mass);
     }
     pub fn decrease_example_mass(&mut self) {
-        self.mass -= 5.0;
+        let mass = (self.example.mass() - MASS_STEP).clamp(MIN_EXAMPLE_MASS, MAX_EXAMPLE_MASS);
+        self.example.set_mass(mass);
     }
 }
 #[derive(Debug)]
@@ -241,6 +855,35 @@ impl<'a> DerefMut for GuideLinePoint<'a> {
     }
 }
 
+//enough points for a circle of `radius` to look round, without huge ones taking forever
+fn point_count(radius: f32) -> u32 {
+    ((radius * PI) as u32).clamp(MIN_POINT_COUNT, MAX_POINT_COUNT)
+}
+fn format_elements(elements: &Elements) -> String {
+    let maybe = |value: Option<f32>| match value {
+        Some(value) => format!("{:.2}", value),
+        None => "n/a".to_string(),
+    };
+    let shape = match elements.conic {
+        Some(conic) => conic.name(),
+        None => "not a conic under 1/r",
+    };
+    format!(
+        "around #{}, {}\nsemi-major axis: {}\neccentricity: {:.3}\n\
+         arg of periapsis: {}\nperiod: {}\nperiapsis: {:.2}\napoapsis: {}",
+        elements.attractor + 1,
+        shape,
+        maybe(elements.semi_major_axis),
+        elements.eccentricity,
+        elements.argument_of_periapsis.map_or_else(
+            || "n/a".to_string(),
+            |a| format!("{:.1} deg", a.to_degrees())
+        ),
+        maybe(elements.period),
+        elements.periapsis,
+        maybe(elements.apoapsis),
+    )
+}
 fn inside(a: (Vector2f, Vector2f), b: Vector2<i32>) -> bool {
     a.0.x < b.x as f32 && a.0.y < b.y as f32 && a.1.x > b.x as f32 && a.1.y > b.y as f32
 }
@@ -0,0 +1,126 @@
+use crate::bodies::ForceLaw;
+//how much harder than holding a body together it is to blow half of it away, from
+//leinhardt and stewart's c* for bodies with no strength of their own
+const DISRUPTION_FACTOR: f32 = 1.9;
+//impacts with less specific energy than this share of the disruption energy just merge
+pub const FRAGMENT_THRESHOLD: f32 = 0.1;
+//slope of the number of debris pieces heavier than m, which goes as m^-b
+const DEBRIS_SLOPE: f32 = 1.85;
+const MAX_DEBRIS: usize = 12;
+//debris smaller than this isn't worth simulating, so impacts that would only make dust merge
+pub const MIN_DEBRIS_RADIUS: f32 = 1.5;
+//at most this share of the impact's kinetic energy goes into throwing the debris out
+const EJECTA_ENERGY_FRACTION: f32 = 0.5;
+//kinetic energy of the impact in the frame of the centre of mass, per unit of total mass
+pub fn specific_impact_energy(mass_a: f32, mass_b: f32, relative_speed: f32) -> f32 {
+    let total = mass_a + mass_b;
+    let reduced = mass_a * mass_b / total;
+    0.5 * reduced * relative_speed * relative_speed / total
+}
+//specific energy that leaves a body of `mass` and `radius` with half its mass, which for
+//1/r^2 is c* times the 3GM/5R it takes to pull a ball apart
+pub fn disruption_energy(law: ForceLaw, gravity: f32, mass: f32, radius: f32) -> f32 {
+    let binding = 0.6 * law.circular_speed(gravity, mass, radius).powi(2);
+    DISRUPTION_FACTOR * binding
+}
+//share of the mass left in the biggest piece, from leinhardt and stewart's universal law
+//which is linear up to 1.8 times the disruption energy and falls off as a power after that
+pub fn largest_remnant_fraction(energy: f32, disruption: f32) -> f32 {
+    let ratio = energy / disruption;
+    if ratio < 1.8 {
+        1.0 - 0.5 * ratio
+    } else {
+        0.1 * (ratio / 1.8).powf(-1.5)
+    }
+}
+//splits `debris` into pieces whose sizes follow the power law, with the k-th biggest going
+//as k^(-1/b). there are as many as fit without any being lighter than `min_mass`, and
+//none if even one piece would be too light
+pub fn debris_masses(debris: f32, min_mass: f32) -> Vec<f32> {
+    if debris < min_mass {
+        return Vec::new();
+    }
+    let pieces = |count: usize| -> Vec<f32> {
+        let weights: Vec<f32> = (1..=count)
+            .map(|k| (k as f32).powf(-1.0 / DEBRIS_SLOPE))
+            .collect();
+        let total: f32 = weights.iter().sum();
+        weights.iter().map(|w| debris * w / total).collect()
+    };
+    (1..=MAX_DEBRIS)
+        .rev()
+        .map(pieces)
+        .find(|masses| masses[masses.len() - 1] >= min_mass)
+        .unwrap_or_else(|| vec![debris])
+}
+//how fast each piece of debris is thrown out relative to the largest remnant, with smaller
+//pieces going faster as m^(-1/6). they start at the escape speed of the remnant, but are
+//slowed so they never carry away more than part of the energy of the impact
+pub fn ejection_speeds(masses: &[f32], escape_speed: f32, impact_energy: f32) -> Vec<f32> {
+    let biggest = masses.iter().cloned().fold(0.0, f32::max);
+    let shape: Vec<f32> = masses
+        .iter()
+        .map(|m| (m / biggest).powf(-1.0 / 6.0))
+        .collect();
+    let energy_per_speed: f32 = masses
+        .iter()
+        .zip(shape.iter())
+        .map(|(m, s)| 0.5 * m * s * s)
+        .sum();
+    let limit = (EJECTA_ENERGY_FRACTION * impact_energy / energy_per_speed).sqrt();
+    let speed = escape_speed.min(limit);
+    shape.iter().map(|s| s * speed).collect()
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remnant_fraction_is_continuous_at_the_breakpoint() {
+        let below = largest_remnant_fraction(1.8 - 1e-4, 1.0);
+        let above = largest_remnant_fraction(1.8 + 1e-4, 1.0);
+        assert!((below - 0.1).abs() < 1e-3);
+        assert!((above - 0.1).abs() < 1e-3);
+    }
+    #[test]
+    fn remnant_fraction_falls_with_energy() {
+        assert_eq!(largest_remnant_fraction(0.0, 1.0), 1.0);
+        assert!((largest_remnant_fraction(1.0, 1.0) - 0.5).abs() < 1e-6);
+        assert!(largest_remnant_fraction(10.0, 1.0) < largest_remnant_fraction(2.0, 1.0));
+    }
+    #[test]
+    fn debris_lighter_than_the_minimum_is_dropped() {
+        assert!(debris_masses(0.5, 1.0).is_empty());
+    }
+    #[test]
+    fn debris_pieces_are_never_lighter_than_the_minimum() {
+        for &(debris, min_mass) in &[(1.0, 1.0), (3.0, 1.0), (10.0, 0.5), (1000.0, 0.01)] {
+            let masses = debris_masses(debris, min_mass);
+            assert!(!masses.is_empty());
+            assert!(masses.len() <= MAX_DEBRIS);
+            assert!(masses.iter().all(|m| *m >= min_mass));
+            let total: f32 = masses.iter().sum();
+            assert!((total - debris).abs() < debris * 1e-5);
+            //biggest first
+            assert!(masses.windows(2).all(|pair| pair[0] >= pair[1]));
+        }
+    }
+    #[test]
+    fn ejection_speeds_start_at_escape_speed() {
+        let speeds = ejection_speeds(&[4.0, 1.0], 2.0, 1e9);
+        assert!((speeds[0] - 2.0).abs() < 1e-6);
+        assert!(speeds[1] > speeds[0]);
+    }
+    #[test]
+    fn ejection_energy_is_capped() {
+        let masses = [4.0, 2.0, 1.0];
+        let impact_energy = 10.0;
+        let speeds = ejection_speeds(&masses, 1000.0, impact_energy);
+        let energy: f32 = masses
+            .iter()
+            .zip(speeds.iter())
+            .map(|(m, v)| 0.5 * m * v * v)
+            .sum();
+        assert!((energy - EJECTA_ENERGY_FRACTION * impact_energy).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,362 @@
+use std::{collections::BTreeMap, fmt};
+
+use serde::{Deserialize, Serialize};
+use sfml::window::Key;
+
+//everything a key can be bound to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyAction {
+    SaveAndQuit,
+    TogglePause,
+    ToggleConsole,
+    FocusNext,
+    FocusPrevious,
+    IncreaseMass,
+    DecreaseMass,
+    EditMass,
+    EditRadius,
+    ClearBodies,
+    RemoveSelected,
+    IncreaseGravity,
+    DecreaseGravity,
+    IncreaseDt,
+    DecreaseDt,
+    MoreSubsteps,
+    FewerSubsteps,
+    StepOnce,
+    ToggleHelp,
+    ToggleOrbitSpawn,
+}
+impl KeyAction {
+    pub const ALL: [KeyAction; 20] = [
+        KeyAction::SaveAndQuit,
+        KeyAction::TogglePause,
+        KeyAction::ToggleConsole,
+        KeyAction::FocusNext,
+        KeyAction::FocusPrevious,
+        KeyAction::IncreaseMass,
+        KeyAction::DecreaseMass,
+        KeyAction::EditMass,
+        KeyAction::EditRadius,
+        KeyAction::ClearBodies,
+        KeyAction::RemoveSelected,
+        KeyAction::IncreaseGravity,
+        KeyAction::DecreaseGravity,
+        KeyAction::IncreaseDt,
+        KeyAction::DecreaseDt,
+        KeyAction::MoreSubsteps,
+        KeyAction::FewerSubsteps,
+        KeyAction::StepOnce,
+        KeyAction::ToggleHelp,
+        KeyAction::ToggleOrbitSpawn,
+    ];
+    fn default_keys(&self) -> &'static [&'static str] {
+        match self {
+            KeyAction::SaveAndQuit => &["Ctrl+S"],
+            KeyAction::TogglePause => &["F"],
+            KeyAction::ToggleConsole => &["Tilde"],
+            KeyAction::FocusNext => &["Right"],
+            KeyAction::FocusPrevious => &["Left"],
+            KeyAction::IncreaseMass => &["Up"],
+            KeyAction::DecreaseMass => &["Down"],
+            KeyAction::EditMass => &["M"],
+            KeyAction::EditRadius => &["R"],
+            KeyAction::ClearBodies => &["C"],
+            KeyAction::RemoveSelected => &["Delete", "BackSpace"],
+            KeyAction::IncreaseGravity => &["Equal"],
+            KeyAction::DecreaseGravity => &["Dash"],
+            KeyAction::IncreaseDt => &["RBracket"],
+            KeyAction::DecreaseDt => &["LBracket"],
+            KeyAction::MoreSubsteps => &["Period"],
+            KeyAction::FewerSubsteps => &["Comma"],
+            KeyAction::StepOnce => &["N"],
+            KeyAction::ToggleHelp => &["F1", "H"],
+            KeyAction::ToggleOrbitSpawn => &["O"],
+        }
+    }
+    pub fn description(&self) -> &'static str {
+        match self {
+            KeyAction::SaveAndQuit => "save the world and quit",
+            KeyAction::TogglePause => "pause or resume",
+            KeyAction::ToggleConsole => "open or close the console",
+            KeyAction::FocusNext => "follow the next body",
+            KeyAction::FocusPrevious => "follow the previous body",
+            KeyAction::IncreaseMass => "more spawn mass",
+            KeyAction::DecreaseMass => "less spawn mass",
+            KeyAction::EditMass => "type in the spawn mass",
+            KeyAction::EditRadius => "type in the spawn radius",
+            KeyAction::ClearBodies => "remove every body",
+            KeyAction::RemoveSelected => "remove the followed body",
+            KeyAction::IncreaseGravity => "stronger gravity",
+            KeyAction::DecreaseGravity => "weaker gravity",
+            KeyAction::IncreaseDt => "longer time step",
+            KeyAction::DecreaseDt => "shorter time step",
+            KeyAction::MoreSubsteps => "more steps per tick",
+            KeyAction::FewerSubsteps => "fewer steps per tick",
+            KeyAction::StepOnce => "step once while paused",
+            KeyAction::ToggleHelp => "show or hide this help",
+            KeyAction::ToggleOrbitSpawn => "switch between dragging and launching into orbit",
+        }
+    }
+}
+//a key together with the modifiers that have to be held for it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyCombo {
+    pub code: Key,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+}
+impl KeyCombo {
+    //parses things like "F", "Ctrl+S" or "Shift+Alt+Delete"
+    pub fn parse(spec: &str) -> Result<KeyCombo, String> {
+        let mut combo = KeyCombo {
+            code: Key::Unknown,
+            ctrl: false,
+            alt: false,
+            shift: false,
+        };
+        let parts: Vec<&str> = spec.split('+').map(str::trim).collect();
+        let (key, modifiers) = parts.split_last().unwrap();
+        for modifier in modifiers {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => combo.ctrl = true,
+                "alt" => combo.alt = true,
+                "shift" => combo.shift = true,
+                _ => {
+                    return Err(format!(
+                        "unknown modifier \"{}\" in \"{}\", expected Ctrl, Alt or Shift",
+                        modifier, spec
+                    ))
+                }
+            }
+        }
+        combo.code = key_from_name(key).ok_or_else(|| {
+            format!(
+                "unknown key \"{}\" in \"{}\", key names look like A, Num1, F5, Space or Delete",
+                key, spec
+            )
+        })?;
+        Ok(combo)
+    }
+}
+impl fmt::Display for KeyCombo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        write!(f, "{}", key_name(self.code))
+    }
+}
+#[derive(Debug, Clone)]
+pub struct Keybindings {
+    bindings: Vec<(KeyCombo, KeyAction)>,
+}
+impl Keybindings {
+    //`overrides` replaces the default keys of each action it mentions
+    pub fn from_overrides(
+        overrides: &BTreeMap<KeyAction, Vec<String>>,
+    ) -> Result<Keybindings, Vec<String>> {
+        let mut bindings: Vec<(KeyCombo, KeyAction)> = Vec::new();
+        let mut errors = Vec::new();
+        for action in KeyAction::ALL.iter() {
+            let specs: Vec<String> = match overrides.get(action) {
+                Some(specs) => specs.clone(),
+                None => action
+                    .default_keys()
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect(),
+            };
+            for spec in specs {
+                match KeyCombo::parse(&spec) {
+                    Ok(combo) => {
+                        if let Some((_, other)) = bindings.iter().find(|(c, _)| *c == combo) {
+                            errors.push(format!(
+                                "{} is bound to both {:?} and {:?}",
+                                combo, other, action
+                            ));
+                        } else {
+                            bindings.push((combo, *action));
+                        }
+                    }
+                    Err(e) => errors.push(format!("keybinding for {:?}: {}", action, e)),
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(Keybindings { bindings })
+        } else {
+            Err(errors)
+        }
+    }
+    pub fn keys_for(&self, action: KeyAction) -> Vec<KeyCombo> {
+        self.bindings
+            .iter()
+            .filter(|(_, a)| *a == action)
+            .map(|(combo, _)| *combo)
+            .collect()
+    }
+    //one line per action that has keys, like "Ctrl+S  save the world and quit"
+    pub fn help_lines(&self) -> Vec<String> {
+        let names: Vec<(String, KeyAction)> = KeyAction::ALL
+            .iter()
+            .map(|action| {
+                let keys: Vec<String> = self
+                    .keys_for(*action)
+                    .iter()
+                    .map(|k| k.to_string())
+                    .collect();
+                (keys.join(", "), *action)
+            })
+            .filter(|(keys, _)| !keys.is_empty())
+            .collect();
+        let width = names.iter().map(|(keys, _)| keys.len()).max().unwrap_or(0);
+        names
+            .iter()
+            .map(|(keys, action)| {
+                format!("{:<width$}  {}", keys, action.description(), width = width)
+            })
+            .collect()
+    }
+    pub fn lookup(&self, code: Key, ctrl: bool, alt: bool, shift: bool) -> Option<KeyAction> {
+        let pressed = KeyCombo {
+            code,
+            ctrl,
+            alt,
+            shift,
+        };
+        self.bindings
+            .iter()
+            .find(|(combo, _)| *combo == pressed)
+            .map(|(_, action)| *action)
+    }
+}
+impl Default for Keybindings {
+    fn default() -> Self {
+        Keybindings::from_overrides(&BTreeMap::new()).expect("the default keybindings are valid")
+    }
+}
+const KEY_NAMES: &[(&str, Key)] = &[
+    ("A", Key::A),
+    ("B", Key::B),
+    ("C", Key::C),
+    ("D", Key::D),
+    ("E", Key::E),
+    ("F", Key::F),
+    ("G", Key::G),
+    ("H", Key::H),
+    ("I", Key::I),
+    ("J", Key::J),
+    ("K", Key::K),
+    ("L", Key::L),
+    ("M", Key::M),
+    ("N", Key::N),
+    ("O", Key::O),
+    ("P", Key::P),
+    ("Q", Key::Q),
+    ("R", Key::R),
+    ("S", Key::S),
+    ("T", Key::T),
+    ("U", Key::U),
+    ("V", Key::V),
+    ("W", Key::W),
+    ("X", Key::X),
+    ("Y", Key::Y),
+    ("Z", Key::Z),
+    ("Num0", Key::Num0),
+    ("Num1", Key::Num1),
+    ("Num2", Key::Num2),
+    ("Num3", Key::Num3),
+    ("Num4", Key::Num4),
+    ("Num5", Key::Num5),
+    ("Num6", Key::Num6),
+    ("Num7", Key::Num7),
+    ("Num8", Key::Num8),
+    ("Num9", Key::Num9),
+    ("Escape", Key::Escape),
+    ("LControl", Key::LControl),
+    ("LShift", Key::LShift),
+    ("LAlt", Key::LAlt),
+    ("LSystem", Key::LSystem),
+    ("RControl", Key::RControl),
+    ("RShift", Key::RShift),
+    ("RAlt", Key::RAlt),
+    ("RSystem", Key::RSystem),
+    ("Menu", Key::Menu),
+    ("LBracket", Key::LBracket),
+    ("RBracket", Key::RBracket),
+    ("SemiColon", Key::SemiColon),
+    ("Comma", Key::Comma),
+    ("Period", Key::Period),
+    ("Quote", Key::Quote),
+    ("Slash", Key::Slash),
+    ("BackSlash", Key::BackSlash),
+    ("Tilde", Key::Tilde),
+    ("Equal", Key::Equal),
+    ("Dash", Key::Dash),
+    ("Space", Key::Space),
+    ("Return", Key::Return),
+    ("BackSpace", Key::BackSpace),
+    ("Tab", Key::Tab),
+    ("PageUp", Key::PageUp),
+    ("PageDown", Key::PageDown),
+    ("End", Key::End),
+    ("Home", Key::Home),
+    ("Insert", Key::Insert),
+    ("Delete", Key::Delete),
+    ("Add", Key::Add),
+    ("Subtract", Key::Subtract),
+    ("Multiply", Key::Multiply),
+    ("Divide", Key::Divide),
+    ("Left", Key::Left),
+    ("Right", Key::Right),
+    ("Up", Key::Up),
+    ("Down", Key::Down),
+    ("Numpad0", Key::Numpad0),
+    ("Numpad1", Key::Numpad1),
+    ("Numpad2", Key::Numpad2),
+    ("Numpad3", Key::Numpad3),
+    ("Numpad4", Key::Numpad4),
+    ("Numpad5", Key::Numpad5),
+    ("Numpad6", Key::Numpad6),
+    ("Numpad7", Key::Numpad7),
+    ("Numpad8", Key::Numpad8),
+    ("Numpad9", Key::Numpad9),
+    ("F1", Key::F1),
+    ("F2", Key::F2),
+    ("F3", Key::F3),
+    ("F4", Key::F4),
+    ("F5", Key::F5),
+    ("F6", Key::F6),
+    ("F7", Key::F7),
+    ("F8", Key::F8),
+    ("F9", Key::F9),
+    ("F10", Key::F10),
+    ("F11", Key::F11),
+    ("F12", Key::F12),
+    ("F13", Key::F13),
+    ("F14", Key::F14),
+    ("F15", Key::F15),
+];
+//names are matched without caring about case, so "delete" and "DELETE" both work
+pub fn key_from_name(name: &str) -> Option<Key> {
+    KEY_NAMES
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, key)| *key)
+}
+pub fn key_name(key: Key) -> &'static str {
+    KEY_NAMES
+        .iter()
+        .find(|(_, k)| *k == key)
+        .map(|(n, _)| *n)
+        .unwrap_or("Unknown")
+}
@@ -0,0 +1,259 @@
+//widgets are keyed by their layer, which never changes, so the RefCells are safe to mutate
+#![allow(clippy::mutable_key_type)]
+use std::{cell::RefCell, collections::BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use sfml::{
+    graphics::{Color, Font, RectangleShape, RenderTarget, Shape, Text, Transformable},
+    system::{Vector2, Vector2f},
+};
+
+use crate::widgets::Widget;
+pub const HEADER_HEIGHT: f32 = 30.0;
+const HEADER_TEXT_SIZE: u32 = 20;
+const MIN_PANEL_WIDTH: f32 = 150.0;
+//which corner or edge of the window a panel sticks to
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+impl Anchor {
+    //where the top left of something `size` big goes, `margin` away from the window edges
+    fn place(&self, window: Vector2f, size: Vector2f, margin: f32) -> Vector2f {
+        let x = match self {
+            Anchor::TopLeft | Anchor::Left | Anchor::BottomLeft => margin,
+            Anchor::Top | Anchor::Center | Anchor::Bottom => (window.x - size.x) / 2.0,
+            Anchor::TopRight | Anchor::Right | Anchor::BottomRight => window.x - size.x - margin,
+        };
+        let y = match self {
+            Anchor::TopLeft | Anchor::Top | Anchor::TopRight => margin,
+            Anchor::Left | Anchor::Center | Anchor::Right => (window.y - size.y) / 2.0,
+            Anchor::BottomLeft | Anchor::Bottom | Anchor::BottomRight => window.y - size.y - margin,
+        };
+        Vector2f::new(x, y)
+    }
+}
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    //side by side, left to right
+    Row,
+    //on top of each other, top to bottom
+    Column,
+}
+//where smaller items sit across a stack, e.g. the bottom of a row
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Align {
+    Start,
+    End,
+}
+#[derive(Debug)]
+pub enum Item {
+    //the layer of a widget
+    Widget(usize),
+    Stack(Stack),
+}
+#[derive(Debug)]
+pub struct Stack {
+    pub direction: Direction,
+    pub spacing: f32,
+    pub align: Align,
+    pub items: Vec<Item>,
+}
+impl Stack {
+    pub fn row(items: Vec<Item>) -> Self {
+        Stack {
+            direction: Direction::Row,
+            spacing: 10.0,
+            align: Align::End,
+            items,
+        }
+    }
+    pub fn column(items: Vec<Item>) -> Self {
+        Stack {
+            direction: Direction::Column,
+            spacing: 10.0,
+            align: Align::Start,
+            items,
+        }
+    }
+}
+type WidgetSet = BTreeSet<RefCell<Box<dyn Widget>>>;
+fn widget(widgets: &WidgetSet, layer: usize) -> &RefCell<Box<dyn Widget>> {
+    widgets.iter().nth(layer).unwrap()
+}
+impl Item {
+    fn size(&self, widgets: &WidgetSet) -> Vector2f {
+        match self {
+            Item::Widget(layer) => widget(widgets, *layer).borrow().size(),
+            Item::Stack(stack) => {
+                let mut total = Vector2f::new(0.0, 0.0);
+                for (i, item) in stack.items.iter().enumerate() {
+                    let size = item.size(widgets);
+                    let gap = if i == 0 { 0.0 } else { stack.spacing };
+                    match stack.direction {
+                        Direction::Row => {
+                            total.x += size.x + gap;
+                            total.y = total.y.max(size.y);
+                        }
+                        Direction::Column => {
+                            total.x = total.x.max(size.x);
+                            total.y += size.y + gap;
+                        }
+                    }
+                }
+                total
+            }
+        }
+    }
+    fn place(&self, widgets: &WidgetSet, position: Vector2f) {
+        match self {
+            Item::Widget(layer) => widget(widgets, *layer).borrow_mut().set_position(position),
+            Item::Stack(stack) => {
+                let outer = self.size(widgets);
+                let mut cursor = position;
+                for item in &stack.items {
+                    let size = item.size(widgets);
+                    let mut at = cursor;
+                    if stack.align == Align::End {
+                        match stack.direction {
+                            Direction::Row => at.y += outer.y - size.y,
+                            Direction::Column => at.x += outer.x - size.x,
+                        }
+                    }
+                    item.place(widgets, at);
+                    match stack.direction {
+                        Direction::Row => cursor.x += size.x + stack.spacing,
+                        Direction::Column => cursor.y += size.y + stack.spacing,
+                    }
+                }
+            }
+        }
+    }
+    fn collect_layers(&self, layers: &mut Vec<usize>) {
+        match self {
+            Item::Widget(layer) => layers.push(*layer),
+            Item::Stack(stack) => stack.items.iter().for_each(|i| i.collect_layers(layers)),
+        }
+    }
+}
+//a titled group of widgets, which can be collapsed down to its title bar
+#[derive(Debug)]
+pub struct Panel {
+    pub title: String,
+    pub anchor: Anchor,
+    pub margin: f32,
+    pub padding: f32,
+    pub collapsed: bool,
+    pub content: Item,
+    position: Vector2f,
+    size: Vector2f,
+}
+impl Panel {
+    pub fn new(title: &str, anchor: Anchor, content: Stack) -> Self {
+        Panel {
+            title: title.to_string(),
+            anchor,
+            margin: 10.0,
+            padding: 10.0,
+            collapsed: false,
+            content: Item::Stack(content),
+            position: Vector2f::new(0.0, 0.0),
+            size: Vector2f::new(0.0, 0.0),
+        }
+    }
+    fn arrange(&mut self, window: Vector2f, widgets: &WidgetSet) {
+        let content = self.content.size(widgets);
+        let width = (content.x + 2.0 * self.padding).max(MIN_PANEL_WIDTH);
+        self.size = if self.collapsed {
+            Vector2f::new(width, HEADER_HEIGHT)
+        } else {
+            Vector2f::new(width, HEADER_HEIGHT + content.y + 2.0 * self.padding)
+        };
+        self.position = self.anchor.place(window, self.size, self.margin);
+        self.content.place(
+            widgets,
+            self.position + Vector2f::new(self.padding, HEADER_HEIGHT + self.padding),
+        );
+    }
+    fn header_contains(&self, point: Vector2f) -> bool {
+        point.x >= self.position.x
+            && point.x <= self.position.x + self.size.x
+            && point.y >= self.position.y
+            && point.y <= self.position.y + HEADER_HEIGHT
+    }
+    fn layers(&self) -> Vec<usize> {
+        let mut layers = Vec::new();
+        self.content.collect_layers(&mut layers);
+        layers
+    }
+    fn draw(&self, target: &mut dyn RenderTarget, font: &Font) {
+        let mut background = RectangleShape::with_size(self.size);
+        background.set_position(self.position);
+        background.set_fill_color(Color::rgba(30, 30, 30, 180));
+        target.draw(&background);
+        let mut header = RectangleShape::with_size(Vector2f::new(self.size.x, HEADER_HEIGHT));
+        header.set_position(self.position);
+        header.set_fill_color(Color::rgb(60, 60, 70));
+        target.draw(&header);
+        let marker = if self.collapsed { "[+]" } else { "[-]" };
+        let mut title = Text::new(
+            &format!("{} {}", marker, self.title),
+            font,
+            HEADER_TEXT_SIZE,
+        );
+        title.set_position((self.position.x + 6.0, self.position.y + 2.0));
+        target.draw(&title);
+    }
+}
+#[derive(Debug, Default)]
+pub struct Layout {
+    pub panels: Vec<Panel>,
+    window: Vector2f,
+}
+impl Layout {
+    pub fn new(panels: Vec<Panel>) -> Self {
+        Layout {
+            panels,
+            window: Vector2f::new(0.0, 0.0),
+        }
+    }
+    //moves every widget to where it belongs in a window of `size`
+    pub fn arrange(&mut self, size: Vector2<u32>, widgets: &WidgetSet) {
+        self.window = Vector2f::new(size.x as f32, size.y as f32);
+        for panel in &mut self.panels {
+            panel.arrange(self.window, widgets);
+        }
+    }
+    //collapses or expands the panel whose title bar is at `point`, returning true if there was one
+    pub fn toggle_at(&mut self, point: Vector2f, widgets: &WidgetSet) -> bool {
+        if let Some(panel) = self.panels.iter_mut().find(|p| p.header_contains(point)) {
+            panel.collapsed = !panel.collapsed;
+            panel.arrange(self.window, widgets);
+            true
+        } else {
+            false
+        }
+    }
+    //widgets in collapsed panels aren't drawn and don't get events
+    pub fn is_hidden(&self, layer: usize) -> bool {
+        self.panels
+            .iter()
+            .any(|p| p.collapsed && p.layers().contains(&layer))
+    }
+    pub fn draw(&self, target: &mut dyn RenderTarget, font: &Font) {
+        for panel in &self.panels {
+            panel.draw(target, font);
+        }
+    }
+}
@@ -1,100 +1,316 @@
 mod bodies;
+mod boundary;
+#[path = "./buttons/button.rs"]
+mod button;
+#[path = "./buttons/checkbox.rs"]
+mod checkbox;
+mod cli;
+#[path = "./buttons/colorpicker.rs"]
+mod colorpicker;
+mod config;
+mod console;
+mod constraints;
+mod events;
+mod forces;
+mod generators;
 mod gui;
+mod impacts;
+mod keybindings;
+mod layout;
+mod orbits;
+mod particles;
+mod presets;
 mod shapes;
 #[path = "./buttons/sliders.rs"]
 mod sliders;
-#[path = "./buttons/testbutton.rs"]
-mod testbutton;
+#[path = "./buttons/status.rs"]
+mod status;
+#[path = "./buttons/textbox.rs"]
+mod textbox;
+mod tides;
+mod timestep;
 mod trails;
+mod trajectory;
+mod triggers;
 mod widgets;
 
-use bodies::WorldSpace;
+use bodies::{WorldSpace, SAVE_PATH};
 use sfml::{
-    graphics::{Color, Font, RenderTarget, RenderWindow},
-    system::Vector2,
-    window::{mouse::Button, Event, Key, Style},
+    graphics::{Color, FloatRect, Font, RenderTarget, RenderWindow, View},
+    system::{Clock, Vector2, Vector2f},
+    window::{mouse::Button, Event, Style},
 };
 use std::f32::consts::PI;
 const CONSOLAS_BYTES: &[u8] = include_bytes!("assets/Consolas.ttf");
 pub const WINDOW_SIZE: (f32, f32) = (1600.0, 1600.0);
 
-use crate::gui::Gui;
+use crate::{
+    cli::Args,
+    config::{Config, CONFIG_PATH},
+    console::Console,
+    events::{EventFeed, SimEvent},
+    gui::Gui,
+    keybindings::{KeyAction, Keybindings},
+    timestep::FixedTimestep,
+    triggers::Condition,
+    widgets::Binding,
+};
+const GRAVITY_FACTOR: f32 = 1.1;
+const DT_FACTOR: f32 = 1.25;
 fn main() {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+    if let Some(message) = &args.exit_message {
+        println!("{}", message);
+        return;
+    }
+    let (config, keybindings) = Config::load(CONFIG_PATH).unwrap_or_else(|errors| {
+        eprintln!("Problems with {}, using the defaults instead:", CONFIG_PATH);
+        for error in errors {
+            eprintln!("  {}", error);
+        }
+        (Config::default(), Keybindings::default())
+    });
     let consolas = Font::from_memory(CONSOLAS_BYTES).unwrap();
-    let mut space = WorldSpace::deserialize("space.json").unwrap_or_default();
+    let mut space = WorldSpace::deserialize(SAVE_PATH).unwrap_or_default();
     space.focused_idx = Some(0);
+    if args.print_events {
+        space.subscribe(Box::new(|event| println!("{}", event.describe())));
+    }
+    if let Some(preset) = &args.preset {
+        match presets::apply(preset, &mut space) {
+            Ok(message) => println!("{}", message),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+    for trigger in &args.triggers {
+        space.add_trigger(*trigger);
+    }
+    if let Some(path) = &args.record {
+        if let Err(e) = space.start_recording(path) {
+            eprintln!("couldn't record to {}: {}", path, e);
+            std::process::exit(1);
+        }
+    }
+    if args.headless {
+        if let Err(e) = run_headless(&mut space, &args) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
     let mut window = RenderWindow::new(
         (WINDOW_SIZE.0 as u32, WINDOW_SIZE.1 as u32),
         "Universe simulator",
-        Style::CLOSE,
+        Style::CLOSE | Style::RESIZE,
         &Default::default(),
     );
-    window.set_framerate_limit(45);
+    window.set_framerate_limit(config.frame_rate_limit);
 
-    let mut gui = Gui::new(window.size(), &consolas);
+    let mut gui = Gui::new(window.size(), &consolas, &space, &config, &keybindings);
+    let mut console = Console::new();
+    let mut feed = EventFeed::default();
+    let mut timestep = FixedTimestep::new();
+    let mut clock = Clock::start();
     'running: while window.is_open() {
         while let Some(event) = window.poll_event() {
-            if handle_events(event, &mut window, &mut space, &mut gui) {
-                space.serialize("space.json").unwrap();
+            if handle_events(
+                event,
+                &mut window,
+                &mut space,
+                &mut gui,
+                &mut console,
+                &keybindings,
+            ) {
+                space.serialize(SAVE_PATH).unwrap();
                 break 'running;
             }
         }
         window.set_active(true);
         window.clear(Color::BLACK);
-        space.advance(&mut window, &Default::default());
-        gui.update_draw(&mut window);
-        gui.update_draw_focused_display(space.prepare_for_gui(), &mut window);
+        let tick_length = 1.0 / space.tick_rate();
+        for _ in 0..timestep.due_ticks(clock.restart().as_seconds(), tick_length) {
+            space.tick();
+        }
+        if space.is_finished() {
+            space.serialize(SAVE_PATH).unwrap();
+            window.close();
+            break 'running;
+        }
+        for event in space.take_events() {
+            feed.push(&event);
+        }
+        space.render(
+            &mut window,
+            &Default::default(),
+            timestep.alpha(tick_length),
+        );
+        gui.update_draw(&mut window, &space);
+        let focused = space.prepare_for_gui();
+        gui.update_draw_focused_display(focused, &space, &mut window);
+        let size = window.size();
+        feed.draw(
+            &mut window,
+            &consolas,
+            Vector2f::new(size.x as f32, size.y as f32),
+        );
+        console.draw(
+            &mut window,
+            &consolas,
+            Vector2f::new(size.x as f32, size.y as f32),
+        );
         window.display();
     }
 }
 
+//ticks as fast as it can without a window until a trigger pauses or stops the world, since
+//nobody could start it again. other triggers might never go off, so it always needs a time
+//to give up at too
+fn run_headless(space: &mut WorldSpace, args: &Args) -> Result<(), String> {
+    let earliest_trigger = space
+        .triggers()
+        .iter()
+        .filter_map(|trigger| match trigger.condition {
+            Condition::Time { time } => Some(time),
+            _ => None,
+        })
+        .min_by(f64::total_cmp);
+    let max_time = match (args.max_time, earliest_trigger) {
+        (Some(a), Some(b)) => a.min(b),
+        (a, b) => a.or(b).ok_or_else(|| {
+            "--headless needs --max-time or a time trigger to know when to give up".to_string()
+        })?,
+    };
+    if space.is_stopped() {
+        space.switch_stopped();
+    }
+    let mut reason = None;
+    while !space.is_stopped() && space.sim_time() < max_time {
+        space.tick();
+        for event in space.take_events() {
+            if let SimEvent::Triggered { trigger, .. } = event {
+                reason = Some(trigger);
+            }
+        }
+    }
+    if let Some(path) = space.stop_recording() {
+        println!("recorded to {}", path);
+    }
+    println!(
+        "{} at t={:.2}",
+        reason.unwrap_or_else(|| "gave up".to_string()),
+        space.sim_time()
+    );
+    if let Some(path) = &args.save {
+        space
+            .serialize(path)
+            .map_err(|e| format!("couldn't save to {}: {}", path, e))?;
+        println!("saved to {}", path);
+    }
+    Ok(())
+}
 fn handle_events<'a, 'b: 'a>(
     event: Event,
     window: &mut RenderWindow,
     space: &'a mut WorldSpace<'b>,
     gui: &'a mut Gui<'b>,
+    console: &mut Console,
+    keybindings: &Keybindings,
 ) -> bool {
     if event == Event::Closed {
         window.close();
-    } else if let Event::KeyPressed {
+    } else if let Event::Resized { width, height } = event {
+        let size = Vector2f::new(width as f32, height as f32);
+        window.set_view(&View::from_rect(&FloatRect::new(0.0, 0.0, size.x, size.y)));
+        space.set_view_size(size);
+        gui.resize(Vector2::new(width, height));
+    } else if (console.is_open() && console_event(&event, window, space, gui, console, keybindings))
+        || gui.handle_event(&event, space)
+    {
+        return false;
+    }
+    if let Event::KeyPressed {
         code,
-        alt: _,
+        alt,
         ctrl,
-        shift: _,
+        shift,
         system: _,
     } = event
     {
-        if code == Key::S && ctrl {
-            window.close();
-            return true;
-        } else if code == Key::F {
-            space.switch_stopped();
-        } else if code == Key::G {
-            println!("You found my dev key!");
-            println!("{:?}", gui.widgets);
-        } else if code == Key::Right {
-            space.advance_focused_idx();
-        } else if code == Key::Up {
-            gui.increase_example_mass();
-        } else if code == Key::Down {
-            gui.decrease_example_mass();
-        } else if code == Key::C {
-            space.clear_bodies();
-        } else if code == Key::Left {
-            space.reduce_focused_index();
-        } else if code == Key::Delete || code == Key::BackSpace {
-            space.remove_selected();
+        match keybindings.lookup(code, ctrl, alt, shift) {
+            Some(KeyAction::SaveAndQuit) => {
+                window.close();
+                return true;
+            }
+            Some(KeyAction::TogglePause) => space.switch_stopped(),
+            Some(KeyAction::ToggleConsole) => console.toggle(),
+            Some(KeyAction::FocusNext) => space.advance_focused_idx(),
+            Some(KeyAction::FocusPrevious) => space.reduce_focused_index(),
+            Some(KeyAction::IncreaseMass) => gui.increase_example_mass(),
+            Some(KeyAction::DecreaseMass) => gui.decrease_example_mass(),
+            Some(KeyAction::EditMass) => gui.focus_text_box(Binding::Mass, space),
+            Some(KeyAction::EditRadius) => gui.focus_text_box(Binding::Radius, space),
+            Some(KeyAction::ClearBodies) => space.clear_bodies(),
+            Some(KeyAction::RemoveSelected) => space.remove_selected(),
+            Some(KeyAction::IncreaseGravity) => space.set_gravity(space.gravity() * GRAVITY_FACTOR),
+            Some(KeyAction::DecreaseGravity) => space.set_gravity(space.gravity() / GRAVITY_FACTOR),
+            Some(KeyAction::IncreaseDt) => space.set_dt(space.dt() * DT_FACTOR),
+            Some(KeyAction::DecreaseDt) => space.set_dt(space.dt() / DT_FACTOR),
+            Some(KeyAction::MoreSubsteps) => space.set_substeps(space.substeps() + 1),
+            Some(KeyAction::FewerSubsteps) => {
+                space.set_substeps(space.substeps().saturating_sub(1))
+            }
+            Some(KeyAction::StepOnce) => space.step_once(),
+            Some(KeyAction::ToggleHelp) => gui.toggle_help(),
+            Some(KeyAction::ToggleOrbitSpawn) => gui.toggle_orbit_spawn(),
+            None => {}
         }
     } else if let Event::MouseButtonPressed { button, x, y } = event {
         if button == Button::Left {
             gui.click(space, Vector2::new(x, y));
         }
-    } else if let Event::MouseButtonReleased { button, x: _, y: _ } = event {
-        if button == Button::Left {
-            gui.release_click(space);
-        }
-    } else if let Event::MouseMoved { x, y } = event {
-        gui.mouse_moved(x, y);
     }
     false
 }
+//while the console is open it gets the keyboard, returning true if it used the event
+fn console_event<'a>(
+    event: &Event,
+    window: &RenderWindow,
+    space: &mut WorldSpace<'a>,
+    gui: &mut Gui<'a>,
+    console: &mut Console,
+    keybindings: &Keybindings,
+) -> bool {
+    match *event {
+        Event::KeyPressed {
+            code,
+            alt,
+            ctrl,
+            shift,
+            system: _,
+        } => {
+            if keybindings.lookup(code, ctrl, alt, shift) == Some(KeyAction::ToggleConsole) {
+                console.toggle();
+            } else if let Some(line) = console.key_pressed(code) {
+                let size = window.size();
+                console.execute(
+                    &line,
+                    space,
+                    gui,
+                    Vector2f::new(size.x as f32, size.y as f32),
+                );
+            }
+            true
+        }
+        Event::TextEntered { unicode } => {
+            console.text_entered(unicode);
+            true
+        }
+        _ => false,
+    }
+}
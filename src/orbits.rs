@@ -0,0 +1,409 @@
+use std::f32::consts::PI;
+
+use sfml::system::Vector2f;
+
+use crate::bodies::{ForceLaw, WorldSpace};
+//the most eccentric orbit a body can be launched into, anything closer to 1 barely comes back
+pub const MAX_ECCENTRICITY: f32 = 0.95;
+//which way round a launched body goes, as seen on screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    Anticlockwise,
+    Clockwise,
+}
+//which body a launched body goes round
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrbitTarget {
+    //the body the camera follows, or the strongest pull if nothing is selected
+    Selected,
+    //whichever body pulls hardest where the new body is put
+    Strongest,
+}
+//how bodies are launched when spawning into orbit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitLaunch {
+    pub direction: Direction,
+    pub target: OrbitTarget,
+    //0 is a circle, bigger numbers start the body at the closest point of a longer orbit
+    pub eccentricity: f32,
+}
+impl Default for OrbitLaunch {
+    fn default() -> Self {
+        OrbitLaunch {
+            direction: Direction::Anticlockwise,
+            target: OrbitTarget::Strongest,
+            eccentricity: 0.0,
+        }
+    }
+}
+fn length(v: Vector2f) -> f32 {
+    (v.x * v.x + v.y * v.y).sqrt()
+}
+//the body whose pull is strongest at `position`, ignoring `skip` and any it is right on top of
+pub fn strongest_pull(
+    space: &WorldSpace,
+    position: Vector2f,
+    skip: Option<usize>,
+) -> Option<usize> {
+    let exponent = space.force_law().exponent();
+    let boundary = space.boundary();
+    space
+        .bodies
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| Some(*i) != skip)
+        .filter_map(|(i, body)| {
+            let distance = length(boundary.separation(position, body.pos2f()));
+            let pull = body.mass() / distance.powf(exponent);
+            //a pull that isn't a number can't be compared with the rest
+            if distance > 0.0 && pull.is_finite() {
+                Some((i, pull))
+            } else {
+                None
+            }
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+}
+//the body a launch from `position` would orbit, if there are any bodies
+pub fn attractor(space: &WorldSpace, position: Vector2f, target: OrbitTarget) -> Option<usize> {
+    let selected = space.focused_idx.filter(|&i| i < space.bodies.len());
+    match (target, selected) {
+        (OrbitTarget::Selected, Some(index)) => Some(index),
+        _ => strongest_pull(space, position, None),
+    }
+}
+//speed at the closest point of an orbit with `eccentricity`, where it is `distance` from `mass`.
+//the far point is distance * (1 + e) / (1 - e) away, and energy and angular momentum are the
+//same at both ends, which works for any force law
+pub fn periapsis_speed(
+    law: ForceLaw,
+    gravity: f32,
+    mass: f32,
+    distance: f32,
+    eccentricity: f32,
+) -> f32 {
+    let eccentricity = eccentricity.clamp(0.0, MAX_ECCENTRICITY);
+    if eccentricity == 0.0 {
+        return law.circular_speed(gravity, mass, distance);
+    }
+    let far = distance * (1.0 + eccentricity) / (1.0 - eccentricity);
+    let climb = law.potential(gravity, mass, far) - law.potential(gravity, mass, distance);
+    (2.0 * climb / (1.0 - (distance / far).powi(2))).sqrt()
+}
+//velocity a body at `position` needs for the orbit described by `launch`, or none if
+//there is nothing to orbit or it is right on top of what it would orbit
+pub fn launch_velocity(
+    space: &WorldSpace,
+    position: Vector2f,
+    launch: &OrbitLaunch,
+) -> Option<Vector2f> {
+    let central = &space.bodies[attractor(space, position, launch.target)?];
+    let offset = position - central.pos2f();
+    let distance = length(offset);
+    if distance <= 0.0 {
+        return None;
+    }
+    let speed = periapsis_speed(
+        space.force_law(),
+        space.gravity(),
+        central.mass(),
+        distance,
+        launch.eccentricity,
+    );
+    let side = match launch.direction {
+        Direction::Anticlockwise => 1.0,
+        Direction::Clockwise => -1.0,
+    };
+    let along = Vector2f::new(offset.y, -offset.x) * (side / distance);
+    Some(central.velocity() + along * speed)
+}
+//what shape an orbit under 1/r^2 is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Conic {
+    Ellipse,
+    Parabola,
+    Hyperbola,
+}
+impl Conic {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Conic::Ellipse => "ellipse",
+            Conic::Parabola => "parabola",
+            Conic::Hyperbola => "hyperbola",
+        }
+    }
+}
+//eccentricities this close to 1 are counted as parabolas
+const PARABOLA_MARGIN: f32 = 1e-3;
+const BISECTION_STEPS: u32 = 40;
+//how many times a turning point is looked for twice as far in or out before giving up
+const MAX_BRACKET_STEPS: u32 = 60;
+//the orbit a body would follow if only its attractor pulled on it, from where it is now.
+//under 1/r the orbits aren't conics, so only the closest and furthest distances are known
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Elements {
+    pub attractor: usize,
+    pub conic: Option<Conic>,
+    //negative for hyperbolas, none for parabolas and under 1/r
+    pub semi_major_axis: Option<f32>,
+    pub eccentricity: f32,
+    //angle from the right of the screen to the closest point, anticlockwise in radians
+    pub argument_of_periapsis: Option<f32>,
+    pub period: Option<f32>,
+    pub periapsis: f32,
+    //none for orbits that never come back
+    pub apoapsis: Option<f32>,
+    //where the attractor is, which is the focus of the conic
+    pub focus: Vector2f,
+    semi_latus_rectum: f32,
+    //direction of the closest point in world coordinates
+    periapsis_direction: Vector2f,
+}
+impl Elements {
+    //whether every number came out as a number, which nearly parabolic orbits and bodies
+    //barely moving can stop them doing
+    fn is_finite(&self) -> bool {
+        [
+            self.semi_major_axis,
+            self.argument_of_periapsis,
+            self.period,
+            self.apoapsis,
+        ]
+        .iter()
+        .flatten()
+        .chain(&[
+            self.eccentricity,
+            self.periapsis,
+            self.semi_latus_rectum,
+            self.periapsis_direction.x,
+            self.periapsis_direction.y,
+        ])
+        .all(|x| x.is_finite())
+    }
+    //points along the conic in world coordinates, stopping `max_distance` from the focus
+    //for orbits that don't close. under 1/r there is no conic, so there are no points
+    pub fn conic_points(&self, count: usize, max_distance: f32) -> Vec<Vector2f> {
+        if self.conic.is_none() {
+            return Vec::new();
+        }
+        let e = self.eccentricity;
+        let p = self.semi_latus_rectum;
+        //how far either side of the closest point the orbit goes before leaving for good
+        let limit = if e < 1.0 - PARABOLA_MARGIN {
+            PI
+        } else {
+            //solves p / (1 + e cos v) = max_distance
+            ((p / max_distance - 1.0) / e).clamp(-1.0, 1.0).acos()
+        };
+        let (dx, dy) = (self.periapsis_direction.x, self.periapsis_direction.y);
+        (0..=count)
+            .map(|i| {
+                let v = -limit + 2.0 * limit * i as f32 / count as f32;
+                let r = p / (1.0 + e * v.cos());
+                let (sin, cos) = v.sin_cos();
+                let x = dx * cos - dy * sin;
+                let y = dx * sin + dy * cos;
+                self.focus + Vector2f::new(x, y) * r
+            })
+            .collect()
+    }
+}
+pub fn cross(a: Vector2f, b: Vector2f) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+fn dot(a: Vector2f, b: Vector2f) -> f32 {
+    a.x * b.x + a.y * b.y
+}
+//the orbit of body `index` around whatever pulls on it hardest, if it can be worked out
+pub fn elements(space: &WorldSpace, index: usize) -> Option<Elements> {
+    raw_elements(space, index).filter(Elements::is_finite)
+}
+fn raw_elements(space: &WorldSpace, index: usize) -> Option<Elements> {
+    let body = space.bodies.get(index)?;
+    let attractor = strongest_pull(space, body.pos2f(), Some(index))?;
+    let central = &space.bodies[attractor];
+    //from the nearest copy of the attractor, which is just it unless the world wraps around
+    let r = space.boundary().separation(central.pos2f(), body.pos2f());
+    let v = body.velocity() - central.velocity();
+    let distance = length(r);
+    if distance <= 0.0 {
+        return None;
+    }
+    let mu = space.gravity() * (central.mass() + body.mass());
+    let h = cross(r, v);
+    let speed_squared = dot(v, v);
+    let focus = body.pos2f() - r;
+    match space.force_law() {
+        ForceLaw::InverseSquare => {
+            let e_vec = (r * (speed_squared - mu / distance) - v * dot(r, v)) / mu;
+            let e = length(e_vec);
+            let energy = speed_squared / 2.0 - mu / distance;
+            let p = h * h / mu;
+            let conic = if (e - 1.0).abs() < PARABOLA_MARGIN {
+                Conic::Parabola
+            } else if e < 1.0 {
+                Conic::Ellipse
+            } else {
+                Conic::Hyperbola
+            };
+            let semi_major_axis = match conic {
+                Conic::Parabola => None,
+                _ => Some(-mu / (2.0 * energy)),
+            };
+            let period = match (conic, semi_major_axis) {
+                (Conic::Ellipse, Some(a)) => Some(2.0 * PI * (a.powi(3) / mu).sqrt()),
+                _ => None,
+            };
+            //a circle has no closest point, so it is measured from wherever the body is
+            let periapsis_direction = if e > 1e-6 { e_vec / e } else { r / distance };
+            //the world's y points down the screen, so angles are flipped to be anticlockwise
+            let argument = (-periapsis_direction.y).atan2(periapsis_direction.x);
+            Some(Elements {
+                attractor,
+                conic: Some(conic),
+                semi_major_axis,
+                eccentricity: e,
+                argument_of_periapsis: Some(argument),
+                period,
+                periapsis: p / (1.0 + e),
+                apoapsis: if conic == Conic::Ellipse {
+                    Some(p / (1.0 - e))
+                } else {
+                    None
+                },
+                focus,
+                semi_latus_rectum: p,
+                periapsis_direction,
+            })
+        }
+        ForceLaw::InverseLinear => {
+            //the radial speed squared over 2 at distance x, which is 0 at the two turning points
+            let energy = speed_squared / 2.0 + mu * distance.ln();
+            let radial = |x: f32| energy - mu * x.ln() - h * h / (2.0 * x * x);
+            let periapsis = if h.abs() < 1e-6 {
+                0.0
+            } else {
+                let inside = bracket(&radial, distance, 0.5)?;
+                bisect(&radial, inside, distance)
+            };
+            //the log potential keeps growing, so every orbit turns back eventually, though
+            //maybe further out than a float goes
+            let outside = bracket(&radial, distance, 2.0)?;
+            let apoapsis = bisect(&radial, outside, distance);
+            Some(Elements {
+                attractor,
+                conic: None,
+                semi_major_axis: None,
+                eccentricity: (apoapsis - periapsis) / (apoapsis + periapsis),
+                argument_of_periapsis: None,
+                period: None,
+                periapsis,
+                apoapsis: Some(apoapsis),
+                focus,
+                semi_latus_rectum: 0.0,
+                periapsis_direction: r / distance,
+            })
+        }
+    }
+}
+//somewhere `f` is negative, trying `start` and then `factor` times further each time
+fn bracket(f: &dyn Fn(f32) -> f32, start: f32, factor: f32) -> Option<f32> {
+    let mut x = start;
+    for _ in 0..MAX_BRACKET_STEPS {
+        if f(x) < 0.0 {
+            return Some(x);
+        }
+        x *= factor;
+    }
+    None
+}
+//where `f` crosses 0 between `outside`, where it is negative, and `inside`
+fn bisect(f: &dyn Fn(f32) -> f32, mut outside: f32, mut inside: f32) -> f32 {
+    for _ in 0..BISECTION_STEPS {
+        let middle = (outside + inside) / 2.0;
+        if f(middle) < 0.0 {
+            outside = middle;
+        } else {
+            inside = middle;
+        }
+    }
+    (outside + inside) / 2.0
+}
+//where a body is headed, as far as the bodies around it go
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fate {
+    //held by the system as a whole but by no one body
+    Bound,
+    //going round the body with this id, which is heavier than it
+    Captured(u64),
+    //far out and going fast enough that the rest can't pull it back
+    Escaping,
+}
+//energy per unit mass of something going `speed` at `distance` from `mass`, which is 0 where
+//it could just get away. nothing ever gets away under 1/r, so there it is 0 at `escape_radius`
+fn specific_energy(
+    space: &WorldSpace,
+    mass: f32,
+    distance: f32,
+    speed: f32,
+    escape_radius: f32,
+) -> f32 {
+    let law = space.force_law();
+    let reference = match law {
+        ForceLaw::InverseLinear => law.potential(space.gravity(), mass, escape_radius),
+        ForceLaw::InverseSquare => 0.0,
+    };
+    0.5 * speed * speed + law.potential(space.gravity(), mass, distance.max(f32::EPSILON))
+        - reference
+}
+//the fate of every body. a body is captured by whatever pulls on it hardest if that is heavier
+//and it is bound to it, and otherwise escaping if it is past the escape radius from the
+//centre of mass of everything else, heading away, with enough energy to get out
+pub fn fates(space: &WorldSpace) -> Vec<Fate> {
+    let boundary = space.boundary();
+    let escape_radius = space.escape_radius();
+    let mut total = 0.0;
+    let mut moment = Vector2f::new(0.0, 0.0);
+    let mut momentum = Vector2f::new(0.0, 0.0);
+    for body in &space.bodies {
+        total += body.mass();
+        moment += body.pos2f() * body.mass();
+        momentum += body.velocity() * body.mass();
+    }
+    space
+        .bodies
+        .iter()
+        .enumerate()
+        .map(|(i, body)| {
+            if let Some(a) = strongest_pull(space, body.pos2f(), Some(i)) {
+                let attractor = &space.bodies[a];
+                let distance = length(boundary.separation(attractor.pos2f(), body.pos2f()));
+                let speed = length(body.velocity() - attractor.velocity());
+                if attractor.mass() > body.mass()
+                    && specific_energy(space, attractor.mass(), distance, speed, escape_radius)
+                        < 0.0
+                {
+                    return Fate::Captured(attractor.id());
+                }
+            }
+            let rest = total - body.mass();
+            if rest <= 0.0 {
+                return Fate::Bound;
+            }
+            let center = (moment - body.pos2f() * body.mass()) / rest;
+            let center_velocity = (momentum - body.velocity() * body.mass()) / rest;
+            let out = boundary.separation(center, body.pos2f());
+            let velocity = body.velocity() - center_velocity;
+            let distance = length(out);
+            if distance > escape_radius
+                && dot(out, velocity) > 0.0
+                && specific_energy(space, rest, distance, length(velocity), escape_radius) >= 0.0
+            {
+                Fate::Escaping
+            } else {
+                Fate::Bound
+            }
+        })
+        .collect()
+}
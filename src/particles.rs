@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use sfml::{
+    graphics::{Color, PrimitiveType, RenderStates, RenderTarget, Vertex},
+    system::Vector2f,
+};
+//how many pixels across a particle is drawn, whatever the zoom
+const PARTICLE_SIZE: f32 = 2.0;
+//something with no mass that bodies pull on, but which pulls on nothing and never hits
+//other particles, so there can be thousands of them
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestParticle {
+    pub x: f32,
+    pub y: f32,
+    //position at the start of the last physics tick, used to interpolate when drawing
+    prev_x: f32,
+    prev_y: f32,
+    pub xv: f32,
+    pub yv: f32,
+    color: Color,
+}
+impl TestParticle {
+    pub fn new(position: Vector2f, velocity: Vector2f, color: Color) -> Self {
+        TestParticle {
+            x: position.x,
+            y: position.y,
+            prev_x: position.x,
+            prev_y: position.y,
+            xv: velocity.x,
+            yv: velocity.y,
+            color,
+        }
+    }
+    pub fn save_previous_position(&mut self) {
+        self.prev_x = self.x;
+        self.prev_y = self.y;
+    }
+    //where the particle was `alpha` of the way through the last physics tick
+    pub fn interpolated_pos(&self, alpha: f32) -> Vector2f {
+        Vector2f::new(
+            self.prev_x + (self.x - self.prev_x) * alpha,
+            self.prev_y + (self.y - self.prev_y) * alpha,
+        )
+    }
+}
+//draws every particle as a small square in one go, which is far cheaper than a shape each
+pub fn draw_particles(
+    particles: &[TestParticle],
+    target: &mut dyn RenderTarget,
+    cam_pos: Vector2f,
+    zoom: f32,
+    screen_center: Vector2f,
+    alpha: f32,
+) {
+    let half = PARTICLE_SIZE / 2.0;
+    let corners = [
+        Vector2f::new(-half, -half),
+        Vector2f::new(half, -half),
+        Vector2f::new(half, half),
+        Vector2f::new(-half, half),
+    ];
+    let mut vertices = Vec::with_capacity(particles.len() * 4);
+    for particle in particles {
+        let position = (particle.interpolated_pos(alpha) - cam_pos) * zoom + screen_center;
+        for corner in corners.iter() {
+            vertices.push(Vertex::with_pos_color(position + *corner, particle.color));
+        }
+    }
+    target.draw_primitives(&vertices, PrimitiveType::Quads, RenderStates::default());
+}
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ParticleSerializable {
+    x: f32,
+    y: f32,
+    xv: f32,
+    yv: f32,
+    color_rgba: (u8, u8, u8, u8),
+}
+impl From<&TestParticle> for ParticleSerializable {
+    fn from(other: &TestParticle) -> Self {
+        let color = other.color;
+        ParticleSerializable {
+            x: other.x,
+            y: other.y,
+            xv: other.xv,
+            yv: other.yv,
+            color_rgba: (color.r, color.g, color.b, color.a),
+        }
+    }
+}
+impl From<ParticleSerializable> for TestParticle {
+    fn from(other: ParticleSerializable) -> Self {
+        let (r, g, b, a) = other.color_rgba;
+        TestParticle::new(
+            Vector2f::new(other.x, other.y),
+            Vector2f::new(other.xv, other.yv),
+            Color::rgba(r, g, b, a),
+        )
+    }
+}
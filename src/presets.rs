@@ -0,0 +1,271 @@
+use std::f32::consts::PI;
+
+use sfml::{graphics::Color, system::Vector2f};
+
+use crate::{
+    bodies::{ForceLaw, SpaceBody, WorldSpace},
+    particles::TestParticle,
+    WINDOW_SIZE,
+};
+//every preset, with what it shows
+pub const PRESETS: [(&str, &str); 5] = [
+    ("two_body", "two equal bodies circling each other"),
+    (
+        "figure_eight",
+        "three bodies chasing each other round a figure eight",
+    ),
+    ("solar", "a sun with six planets on circular orbits"),
+    (
+        "binary",
+        "two stars circling each other with a planet around both",
+    ),
+    (
+        "trojans",
+        "a planet with trojans sitting 60 degrees ahead and behind it",
+    ),
+];
+//the figure eight solution for G = 1 and masses of 1, from Chenciner and Montgomery
+const FIGURE_EIGHT_POSITION: (f32, f32) = (0.970_004_4, -0.243_087_5);
+const FIGURE_EIGHT_VELOCITY: (f32, f32) = (-0.932_407_4, -0.864_731_5);
+//a body that hasn't been turned into a `SpaceBody` yet, relative to the middle of the scene
+pub struct Seed {
+    pub position: Vector2f,
+    pub velocity: Vector2f,
+    pub mass: f32,
+    pub radius: f32,
+    pub color: Color,
+}
+impl Seed {
+    pub fn new(position: Vector2f, mass: f32, radius: f32, color: Color) -> Self {
+        Seed {
+            position,
+            velocity: Vector2f::new(0.0, 0.0),
+            mass,
+            radius,
+            color,
+        }
+    }
+}
+fn center() -> Vector2f {
+    Vector2f::new(WINDOW_SIZE.0 / 2.0, WINDOW_SIZE.1 / 2.0)
+}
+fn rotate(v: Vector2f, angle: f32) -> Vector2f {
+    let (sin, cos) = angle.sin_cos();
+    Vector2f::new(v.x * cos - v.y * sin, v.x * sin + v.y * cos)
+}
+//velocity of something at `offset` from what it orbits, going anticlockwise on screen
+fn orbit_velocity(offset: Vector2f, speed: f32) -> Vector2f {
+    let distance = (offset.x * offset.x + offset.y * offset.y).sqrt();
+    Vector2f::new(offset.y, -offset.x) * (speed / distance)
+}
+//gives every seed the velocity it needs to turn with the whole group at `angular_speed`
+//around their centre of mass, which keeps shapes like the trojan triangle rigid
+fn spin(seeds: &mut [Seed], angular_speed: f32) {
+    let mass: f32 = seeds.iter().map(|s| s.mass).sum();
+    let center = seeds
+        .iter()
+        .fold(Vector2f::new(0.0, 0.0), |sum, s| sum + s.position * s.mass)
+        / mass;
+    for seed in seeds {
+        let offset = seed.position - center;
+        seed.velocity = Vector2f::new(offset.y, -offset.x) * angular_speed;
+    }
+}
+//speed of two bodies `distance` apart around each other, as an angular speed
+fn pair_angular_speed(law: ForceLaw, gravity: f32, total_mass: f32, distance: f32) -> f32 {
+    law.circular_speed(gravity, total_mass, distance) / distance
+}
+fn two_body(law: ForceLaw, gravity: f32) -> Vec<Seed> {
+    let distance = 300.0;
+    let mut seeds = vec![
+        Seed::new(
+            Vector2f::new(-distance / 2.0, 0.0),
+            50.0,
+            20.0,
+            Color::WHITE,
+        ),
+        Seed::new(
+            Vector2f::new(distance / 2.0, 0.0),
+            50.0,
+            20.0,
+            Color::rgb(40, 60, 110),
+        ),
+    ];
+    spin(
+        &mut seeds,
+        pair_angular_speed(law, gravity, 100.0, distance),
+    );
+    seeds
+}
+//the figure eight only works with real gravity, so this always uses 1/r^2
+fn figure_eight(gravity: f32) -> Vec<Seed> {
+    let scale = 250.0;
+    let mass = 50.0;
+    let speed_scale = (gravity * mass / scale).sqrt();
+    let (px, py) = FIGURE_EIGHT_POSITION;
+    let (vx, vy) = FIGURE_EIGHT_VELOCITY;
+    let colors = [
+        Color::rgb(230, 90, 90),
+        Color::rgb(90, 200, 110),
+        Color::rgb(90, 140, 230),
+    ];
+    let positions = [(px, py), (-px, -py), (0.0, 0.0)];
+    let velocities = [(-vx / 2.0, -vy / 2.0), (-vx / 2.0, -vy / 2.0), (vx, vy)];
+    positions
+        .iter()
+        .zip(velocities.iter())
+        .zip(colors.iter())
+        .map(|((p, v), color)| Seed {
+            position: Vector2f::new(p.0, p.1) * scale,
+            velocity: Vector2f::new(v.0, v.1) * speed_scale,
+            mass,
+            radius: 8.0,
+            color: *color,
+        })
+        .collect()
+}
+fn solar(law: ForceLaw, gravity: f32) -> Vec<Seed> {
+    let sun_mass = 500.0;
+    let mut seeds = vec![Seed::new(
+        Vector2f::new(0.0, 0.0),
+        sun_mass,
+        40.0,
+        Color::rgb(255, 210, 80),
+    )];
+    //distance, mass, radius and colour of each planet
+    let planets = [
+        (120.0, 0.5, 5.0, Color::rgb(170, 160, 150)),
+        (180.0, 2.0, 8.0, Color::rgb(230, 200, 140)),
+        (250.0, 2.5, 9.0, Color::rgb(80, 140, 230)),
+        (330.0, 1.0, 6.0, Color::rgb(210, 100, 60)),
+        (500.0, 20.0, 18.0, Color::rgb(220, 170, 120)),
+        (700.0, 12.0, 15.0, Color::rgb(230, 210, 150)),
+    ];
+    for (i, (distance, mass, radius, color)) in planets.iter().enumerate() {
+        //spread out around the sun so they don't start in a line
+        let offset = rotate(Vector2f::new(*distance, 0.0), i as f32 * 2.4);
+        let mut planet = Seed::new(offset, *mass, *radius, *color);
+        planet.velocity = orbit_velocity(offset, law.circular_speed(gravity, sun_mass, *distance));
+        seeds.push(planet);
+    }
+    seeds
+}
+fn binary(law: ForceLaw, gravity: f32) -> Vec<Seed> {
+    let star_mass = 150.0;
+    let separation = 160.0;
+    let mut seeds = vec![
+        Seed::new(
+            Vector2f::new(-separation / 2.0, 0.0),
+            star_mass,
+            25.0,
+            Color::rgb(255, 200, 120),
+        ),
+        Seed::new(
+            Vector2f::new(separation / 2.0, 0.0),
+            star_mass,
+            25.0,
+            Color::rgb(150, 190, 255),
+        ),
+    ];
+    spin(
+        &mut seeds,
+        pair_angular_speed(law, gravity, 2.0 * star_mass, separation),
+    );
+    //far enough out that the pair pulls on it like one star
+    let distance = 4.0 * separation;
+    let offset = Vector2f::new(0.0, -distance);
+    let mut planet = Seed::new(offset, 2.0, 8.0, Color::rgb(90, 200, 140));
+    planet.velocity = orbit_velocity(
+        offset,
+        law.circular_speed(gravity, 2.0 * star_mass, distance),
+    );
+    seeds.push(planet);
+    seeds
+}
+fn trojans(law: ForceLaw, gravity: f32) -> Vec<Seed> {
+    let sun_mass = 500.0;
+    let planet_mass = 10.0;
+    let distance = 400.0;
+    let planet_offset = Vector2f::new(distance, 0.0);
+    let mut seeds = vec![
+        Seed::new(
+            Vector2f::new(0.0, 0.0),
+            sun_mass,
+            40.0,
+            Color::rgb(255, 210, 80),
+        ),
+        Seed::new(planet_offset, planet_mass, 15.0, Color::rgb(220, 170, 120)),
+    ];
+    //L4 and L5 make equilateral triangles with the sun and planet for any power law
+    for side in [1.0, -1.0].iter() {
+        for nudge in [-0.04, 0.0, 0.04].iter() {
+            let angle = *side * (PI / 3.0 + nudge);
+            seeds.push(Seed::new(
+                rotate(planet_offset, angle),
+                0.01,
+                4.0,
+                Color::rgb(160, 200, 160),
+            ));
+        }
+    }
+    let angular_speed = pair_angular_speed(law, gravity, sun_mass + planet_mass, distance);
+    spin(&mut seeds, angular_speed);
+    seeds
+}
+//replaces the bodies in `space` with the preset called `name`, using its gravity and force law
+pub fn apply(name: &str, space: &mut WorldSpace) -> Result<String, String> {
+    let gravity = space.gravity();
+    let law = space.force_law();
+    let mut message = format!("loaded preset {}", name);
+    let seeds = match name {
+        "two_body" => two_body(law, gravity),
+        "figure_eight" => {
+            if law != ForceLaw::InverseSquare {
+                space.set_force_law(ForceLaw::InverseSquare);
+                message.push_str(", switched to the inverse_square force law it needs");
+            }
+            figure_eight(gravity)
+        }
+        "solar" => solar(law, gravity),
+        "binary" => binary(law, gravity),
+        "trojans" => trojans(law, gravity),
+        _ => {
+            let names: Vec<&str> = PRESETS.iter().map(|(name, _)| *name).collect();
+            return Err(format!(
+                "there is no preset called {}, the presets are {}",
+                name,
+                names.join(", ")
+            ));
+        }
+    };
+    let bodies = into_bodies(seeds);
+    space.replace_bodies(bodies);
+    Ok(message)
+}
+//places the seeds around the middle of the window
+pub fn into_bodies<'a>(seeds: Vec<Seed>) -> Vec<SpaceBody<'a>> {
+    seeds
+        .into_iter()
+        .enumerate()
+        .map(|(i, seed)| {
+            let position = center() + seed.position;
+            SpaceBody::new(
+                (position.x, position.y),
+                seed.mass,
+                seed.radius,
+                seed.velocity.x,
+                seed.velocity.y,
+                false,
+                seed.color,
+                i,
+            )
+        })
+        .collect()
+}
+//places the seeds around the middle of the window as massless test particles
+pub fn into_particles(seeds: Vec<Seed>) -> Vec<TestParticle> {
+    seeds
+        .into_iter()
+        .map(|seed| TestParticle::new(center() + seed.position, seed.velocity, seed.color))
+        .collect()
+}
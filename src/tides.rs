@@ -0,0 +1,20 @@
+use crate::{orbits::Elements, PI};
+//a body only tears apart near one at least this many times heavier
+pub const MIN_TIDAL_MASS_RATIO: f32 = 10.0;
+pub const MIN_FRAGMENTS: u32 = 2;
+pub const MAX_FRAGMENTS: u32 = 16;
+//bodies are balls, so their volume goes as the radius cubed like merging assumes
+pub fn density(mass: f32, radius: f32) -> f32 {
+    mass / (4.0 / 3.0 * PI * radius.powi(3))
+}
+//how close a body held together only by its own pull can get to `primary` before it is
+//pulled apart, from d = 2.44 R (density of primary / density of body)^(1/3)
+pub fn roche_limit(primary_mass: f32, primary_radius: f32, mass: f32, radius: f32) -> f32 {
+    let ratio = density(primary_mass, primary_radius) / density(mass, radius);
+    2.44 * primary_radius * ratio.cbrt()
+}
+//how far from a body of `mass` things can orbit it without its attractor taking them, using
+//the closest approach so it is the smallest the sphere gets
+pub fn hill_radius(elements: &Elements, mass: f32, attractor_mass: f32) -> f32 {
+    elements.periapsis * (mass / (3.0 * attractor_mass)).cbrt()
+}
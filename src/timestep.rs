@@ -0,0 +1,30 @@
+//the most ticks run for one frame, any time still owed after that is dropped
+//so a slow frame can't make the next one even slower
+pub const MAX_TICKS_PER_FRAME: u32 = 10;
+#[derive(Debug, Default)]
+pub struct FixedTimestep {
+    accumulator: f32,
+}
+impl FixedTimestep {
+    pub fn new() -> Self {
+        FixedTimestep { accumulator: 0.0 }
+    }
+    //adds `elapsed` seconds and returns how many ticks of `tick_length` seconds are due
+    pub fn due_ticks(&mut self, elapsed: f32, tick_length: f32) -> u32 {
+        self.accumulator += elapsed;
+        let mut ticks = 0;
+        while self.accumulator >= tick_length {
+            if ticks == MAX_TICKS_PER_FRAME {
+                self.accumulator %= tick_length;
+                break;
+            }
+            self.accumulator -= tick_length;
+            ticks += 1;
+        }
+        ticks
+    }
+    //how far the leftover time is into the next tick, from 0 to 1
+    pub fn alpha(&self, tick_length: f32) -> f32 {
+        (self.accumulator / tick_length).clamp(0.0, 1.0)
+    }
+}
@@ -1,4 +1,3 @@
-use crate::WINDOW_SIZE;
 use sfml::{
     graphics::{CircleShape, Drawable, RenderTarget, Transformable},
     system::Vector2f,
@@ -13,10 +12,16 @@ pub struct TrailPoint<'a> {
     y: f32,
 }
 impl TrailPoint<'_> {
-    pub fn draw(&mut self, target: &mut dyn RenderTarget, cam_pos: Vector2f) {
+    pub fn draw(
+        &mut self,
+        target: &mut dyn RenderTarget,
+        cam_pos: Vector2f,
+        zoom: f32,
+        screen_center: Vector2f,
+    ) {
         self.circle.set_position((
-            self.x - self.circle.radius() - cam_pos.x + WINDOW_SIZE.0 / 2.0,
-            self.y - self.circle.radius() - cam_pos.y + WINDOW_SIZE.1 / 2.0,
+            (self.x - cam_pos.x) * zoom - self.circle.radius() + screen_center.x,
+            (self.y - cam_pos.y) * zoom - self.circle.radius() + screen_center.y,
         ));
         self.circle.draw(target, Default::default());
     }
@@ -31,9 +36,9 @@ impl TrailPoint<'_> {
             y,
         }
     }
-    pub fn update(&mut self) -> bool {
+    pub fn update(&mut self, max_age: usize) -> bool {
         self.age += 1;
-        self.age > DEATH_AGE
+        self.age > max_age
     }
 }
 #[allow(unused)]
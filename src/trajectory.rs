@@ -0,0 +1,49 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+
+use crate::{bodies::SpaceBody, events::SimEvent};
+//a csv file with where every body was after each tick, and events as comment lines
+//starting with # in between
+#[derive(Debug)]
+pub struct TrajectoryLog {
+    path: String,
+    writer: BufWriter<File>,
+}
+impl TrajectoryLog {
+    pub fn create(path: &str) -> io::Result<TrajectoryLog> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "t,id,x,y,vx,vy,mass")?;
+        Ok(TrajectoryLog {
+            path: path.to_string(),
+            writer,
+        })
+    }
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+    pub fn sample(&mut self, time: f64, bodies: &[SpaceBody]) -> io::Result<()> {
+        for body in bodies {
+            let (position, velocity) = (body.pos2f(), body.velocity());
+            writeln!(
+                self.writer,
+                "{},{},{},{},{},{},{}",
+                time,
+                body.id(),
+                position.x,
+                position.y,
+                velocity.x,
+                velocity.y,
+                body.mass()
+            )?;
+        }
+        Ok(())
+    }
+    pub fn event(&mut self, event: &SimEvent) -> io::Result<()> {
+        writeln!(self.writer, "# {}", event.describe())
+    }
+    pub fn finish(mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
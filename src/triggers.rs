@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+//what a trigger does when it goes off
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    //stops time, which can be started again. headless runs have nobody to start it again, so
+    //they end
+    Pause,
+    //ends the run, closing the window like quitting would
+    Stop,
+}
+//when a trigger goes off. bodies are named by id, which is their index when the world was made
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Condition {
+    //any two bodies hit
+    Collision,
+    //the simulated time reaches `time`
+    Time { time: f64 },
+    //body `id` is escaping
+    Escape { id: u64 },
+    //the total energy has drifted more than `limit` of itself since the trigger was set
+    EnergyError { limit: f64 },
+    //bodies `a` and `b` are closer than `distance`
+    Near { a: u64, b: u64, distance: f32 },
+}
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Trigger {
+    pub action: Action,
+    pub condition: Condition,
+    //whether the condition held after the last step, so it only goes off when it starts to
+    #[serde(skip)]
+    pub held: bool,
+    //the energy when an energy trigger was first checked
+    #[serde(skip)]
+    pub reference_energy: Option<f64>,
+}
+pub const TRIGGER_USAGE: &str =
+    "pause|stop collision | time T | escape ID | energy LIMIT | near A B DISTANCE";
+impl Trigger {
+    pub fn new(action: Action, condition: Condition) -> Self {
+        Trigger {
+            action,
+            condition,
+            held: false,
+            reference_energy: None,
+        }
+    }
+    //reads a trigger from words like `stop time 100` or `pause near 0 1 25`
+    pub fn parse(words: &[&str]) -> Result<Trigger, String> {
+        //infinities and nans would make triggers that can never go off
+        let number = |word: &str| match word.parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(value),
+            _ => Err(format!("{} isn't a number", word)),
+        };
+        let id = |word: &str| {
+            word.parse::<u64>()
+                .map_err(|_| format!("{} isn't a body id", word))
+        };
+        let (action, rest) = match words {
+            ["pause", rest @ ..] => (Action::Pause, rest),
+            ["stop", rest @ ..] => (Action::Stop, rest),
+            _ => return Err(format!("a trigger looks like {}", TRIGGER_USAGE)),
+        };
+        let condition = match rest {
+            ["collision"] => Condition::Collision,
+            ["time", time] => Condition::Time {
+                time: number(time)?,
+            },
+            ["escape", body] => Condition::Escape { id: id(body)? },
+            ["energy", limit] => match number(limit)? {
+                limit if limit > 0.0 => Condition::EnergyError { limit },
+                _ => return Err("the energy error limit has to be more than 0".to_string()),
+            },
+            ["near", a, b, distance] => match number(distance)? {
+                distance if distance > 0.0 => Condition::Near {
+                    a: id(a)?,
+                    b: id(b)?,
+                    distance: distance as f32,
+                },
+                _ => return Err("the distance has to be more than 0".to_string()),
+            },
+            _ => return Err(format!("a trigger looks like {}", TRIGGER_USAGE)),
+        };
+        Ok(Trigger::new(action, condition))
+    }
+    //starts over, for when the world it was watching is swapped out
+    pub fn reset(&mut self) {
+        self.held = false;
+        self.reference_energy = None;
+    }
+    pub fn describe(&self) -> String {
+        let action = match self.action {
+            Action::Pause => "pause",
+            Action::Stop => "stop",
+        };
+        let condition = match self.condition {
+            Condition::Collision => "any bodies collide".to_string(),
+            Condition::Time { time } => format!("t reaches {}", time),
+            Condition::Escape { id } => format!("body {} escapes", id),
+            Condition::EnergyError { limit } => {
+                format!("the energy drifts by more than {:e} of itself", limit)
+            }
+            Condition::Near { a, b, distance } => {
+                format!("bodies {} and {} are within {}", a, b, distance)
+            }
+        };
+        format!("{} when {}", action, condition)
+    }
+}
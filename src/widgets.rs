@@ -1,26 +1,410 @@
 use sfml::{
-    graphics::{CircleShape, RenderTarget},
+    graphics::{Color, Font, RenderTarget, Shape},
     system::Vector2f,
+    window::{mouse::Button, Event, Key},
 };
 use std::{fmt::Debug, usize};
 
-use crate::{bodies::WorldSpace, gui::Gui};
+use crate::{
+    bodies::WorldSpace,
+    gui::ExamplePlanet,
+    orbits::{Direction, OrbitTarget, MAX_ECCENTRICITY},
+    sliders::ColorType,
+    tides::{MAX_FRAGMENTS, MIN_FRAGMENTS},
+};
 #[derive(Debug)]
 pub enum WidgetKind {
-    TestButton,
+    Button,
+    Checkbox,
+    ColorPicker,
+    Label,
     Slider,
+    TextBox,
+}
+//everything a widget can be told about, in window coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WidgetEvent {
+    MouseDown { x: i32, y: i32 },
+    MouseUp { x: i32, y: i32 },
+    MouseMove { x: i32, y: i32 },
+    Wheel { delta: f32, x: i32, y: i32 },
+    Key { code: Key, ctrl: bool, shift: bool },
+    Text(char),
+    FocusGained,
+    FocusLost,
+    //the mouse moved onto or off the widget
+    MouseEnter,
+    MouseLeave,
+}
+impl WidgetEvent {
+    //only the left mouse button is passed on to widgets
+    pub fn from_sfml(event: &Event) -> Option<WidgetEvent> {
+        match *event {
+            Event::MouseButtonPressed {
+                button: Button::Left,
+                x,
+                y,
+            } => Some(WidgetEvent::MouseDown { x, y }),
+            Event::MouseButtonReleased {
+                button: Button::Left,
+                x,
+                y,
+            } => Some(WidgetEvent::MouseUp { x, y }),
+            Event::MouseMoved { x, y } => Some(WidgetEvent::MouseMove { x, y }),
+            Event::MouseWheelScrolled { delta, x, y, .. } => {
+                Some(WidgetEvent::Wheel { delta, x, y })
+            }
+            Event::KeyPressed {
+                code, ctrl, shift, ..
+            } => Some(WidgetEvent::Key { code, ctrl, shift }),
+            Event::TextEntered { unicode } => Some(WidgetEvent::Text(unicode)),
+            _ => None,
+        }
+    }
+}
+//something a widget wants done to the gui or the world
+#[derive(Debug, Clone, PartialEq)]
+pub enum WidgetAction {
+    Set(Binding, f32),
+    SetColor(Color),
+    SetFlag(Flag, bool),
+    Run(Command),
+    ReleaseFocus,
+}
+//something a button can do that isn't changing a value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Command {
+    TogglePause,
+    ClearBodies,
+    //gives the selected body the spawn colour
+    RecolorSelected,
+    Save,
+    LoadPreset(&'static str),
+}
+impl Command {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Command::TogglePause => "Pauses or resumes the simulation",
+            Command::ClearBodies => "Removes every body, click twice to confirm",
+            Command::RecolorSelected => "Gives the selected body the colour picked above",
+            Command::Save => "Saves the world so it is loaded next time",
+            Command::LoadPreset(_) => {
+                "Replaces every body with this scene, using the current gravity and force law"
+            }
+        }
+    }
+}
+//an on or off setting that a checkbox can show and change
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Flag {
+    Paused,
+    Trails,
+    ImmovableSpawn,
+    //clicking puts a body straight into orbit instead of dragging to launch it
+    OrbitSpawn,
+    Clockwise,
+    OrbitSelected,
+    ParticleSpawn,
+    AbsorbParticles,
+    TidalDisruption,
+    HillSphere,
+    Fragmentation,
+}
+impl Flag {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Flag::Paused => "Stops the simulation, the world can still be stepped by hand",
+            Flag::Trails => "Draws a fading trail behind every body",
+            Flag::ImmovableSpawn => "New bodies stay where they are put and only pull on others",
+            Flag::OrbitSpawn => {
+                "Clicking puts a body into orbit around whatever pulls on it hardest there"
+            }
+            Flag::Clockwise => "Bodies put into orbit go clockwise instead of anticlockwise",
+            Flag::OrbitSelected => "Bodies put into orbit go around the selected body instead",
+            Flag::ParticleSpawn => {
+                "New bodies are massless test particles, pulled on but pulling on nothing"
+            }
+            Flag::AbsorbParticles => {
+                "Test particles that hit a body disappear instead of passing through"
+            }
+            Flag::TidalDisruption => {
+                "Bodies inside the Roche limit of a much heavier body break into fragments"
+            }
+            Flag::HillSphere => "Draws the region the selected body can hold onto moons in",
+            Flag::Fragmentation => {
+                "Hard enough impacts shatter bodies into debris instead of merging"
+            }
+        }
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            Flag::Paused => "paused",
+            Flag::Trails => "trails",
+            Flag::ImmovableSpawn => "spawn immovable",
+            Flag::OrbitSpawn => "launch into orbit",
+            Flag::Clockwise => "clockwise",
+            Flag::OrbitSelected => "orbit selected",
+            Flag::ParticleSpawn => "spawn particles",
+            Flag::AbsorbParticles => "absorb particles",
+            Flag::TidalDisruption => "tidal disruption",
+            Flag::HillSphere => "show hill sphere",
+            Flag::Fragmentation => "fragmentation",
+        }
+    }
+    pub fn get(&self, example: &ExamplePlanet, space: &WorldSpace) -> bool {
+        match self {
+            Flag::Paused => space.is_stopped(),
+            Flag::Trails => space.trails_enabled(),
+            Flag::ImmovableSpawn => example.immovable(),
+            Flag::OrbitSpawn => example.orbit_spawn(),
+            Flag::Clockwise => example.orbit().direction == Direction::Clockwise,
+            Flag::OrbitSelected => example.orbit().target == OrbitTarget::Selected,
+            Flag::ParticleSpawn => example.particle(),
+            Flag::AbsorbParticles => space.absorb_particles_enabled(),
+            Flag::TidalDisruption => space.tidal_disruption(),
+            Flag::HillSphere => space.show_hill_sphere(),
+            Flag::Fragmentation => space.fragmentation(),
+        }
+    }
+    pub fn set(&self, value: bool, example: &mut ExamplePlanet, space: &mut WorldSpace) {
+        match self {
+            Flag::Paused => {
+                if space.is_stopped() != value {
+                    space.switch_stopped();
+                }
+            }
+            Flag::Trails => space.set_trails_enabled(value),
+            Flag::ImmovableSpawn => example.set_immovable(value),
+            Flag::OrbitSpawn => example.set_orbit_spawn(value),
+            Flag::Clockwise => {
+                example.orbit_mut().direction = if value {
+                    Direction::Clockwise
+                } else {
+                    Direction::Anticlockwise
+                }
+            }
+            Flag::OrbitSelected => {
+                example.orbit_mut().target = if value {
+                    OrbitTarget::Selected
+                } else {
+                    OrbitTarget::Strongest
+                }
+            }
+            Flag::ParticleSpawn => example.set_particle(value),
+            Flag::AbsorbParticles => space.set_absorb_particles(value),
+            Flag::TidalDisruption => space.set_tidal_disruption(value),
+            Flag::HillSphere => space.set_show_hill_sphere(value),
+            Flag::Fragmentation => space.set_fragmentation(value),
+        }
+    }
+}
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventResponse {
+    //the event wasn't for this widget, so it can go on to the world
+    Ignored,
+    Consumed(Vec<WidgetAction>),
+}
+impl EventResponse {
+    pub fn consumed() -> Self {
+        EventResponse::Consumed(Vec::new())
+    }
 }
 
 pub trait Widget {
     fn get_bounds(&self) -> (Vector2f, Vector2f);
     fn get_layer(&self) -> usize;
-    fn draw(&self, target: &mut dyn RenderTarget);
+    //how much room the widget needs in a layout, including any label
+    fn size(&self) -> Vector2f;
+    //moves the top left of the widget's layout box to `position`
+    fn set_position(&mut self, position: Vector2f);
+    fn draw(&self, target: &mut dyn RenderTarget, font: &Font);
     fn widget_type(&self) -> WidgetKind;
-    fn click(&mut self, gui: &Gui, space: &mut WorldSpace);
-    fn release_click(&mut self, example_planet: &mut CircleShape, space: &mut WorldSpace);
-    fn is_click_held(&self) -> bool;
+    fn handle_event(&mut self, event: &WidgetEvent) -> EventResponse;
+    //whether clicking the widget should give it the keyboard
+    fn accepts_focus(&self) -> bool {
+        false
+    }
     fn debug_string(&self) -> String;
-    fn mouse_moved(&mut self, planet: &mut CircleShape, x: i32, y: i32);
+    //shown next to the mouse after it has rested on the widget for a moment
+    fn tooltip(&self) -> Option<String> {
+        None
+    }
+    //the value this widget shows, if it shows one
+    fn binding(&self) -> Option<Binding> {
+        None
+    }
+    //called every frame so the widget can follow changes made by something else
+    fn sync(&mut self, _example: &ExamplePlanet, _space: &WorldSpace) {}
+}
+//a value that a widget can read and change
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Binding {
+    Color(ColorType),
+    Mass,
+    Radius,
+    Gravity,
+    Dt,
+    TrailLength,
+    Zoom,
+    Substeps,
+    TickRate,
+    Eccentricity,
+    Fragments,
+    EscapeRadius,
+}
+impl Binding {
+    pub const ALL: [Binding; 15] = [
+        Binding::Color(ColorType::Red),
+        Binding::Color(ColorType::Green),
+        Binding::Color(ColorType::Blue),
+        Binding::Color(ColorType::Alpha),
+        Binding::Mass,
+        Binding::Radius,
+        Binding::Gravity,
+        Binding::Dt,
+        Binding::TrailLength,
+        Binding::Zoom,
+        Binding::Substeps,
+        Binding::TickRate,
+        Binding::Eccentricity,
+        Binding::Fragments,
+        Binding::EscapeRadius,
+    ];
+    //what the console calls it, which unlike `name` never has spaces
+    pub fn key(&self) -> &'static str {
+        match self {
+            Binding::TrailLength => "trail_length",
+            Binding::Substeps => "substeps",
+            Binding::TickRate => "tick_rate",
+            Binding::EscapeRadius => "escape_radius",
+            _ => self.name(),
+        }
+    }
+    pub fn from_key(key: &str) -> Option<Binding> {
+        Binding::ALL.iter().copied().find(|b| b.key() == key)
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            Binding::Color(ColorType::Red) => "red",
+            Binding::Color(ColorType::Green) => "green",
+            Binding::Color(ColorType::Blue) => "blue",
+            Binding::Color(ColorType::Alpha) => "alpha",
+            Binding::Mass => "mass",
+            Binding::Radius => "radius",
+            Binding::Gravity => "gravity",
+            Binding::Dt => "dt",
+            Binding::TrailLength => "trail length",
+            Binding::Zoom => "zoom",
+            Binding::Substeps => "steps per tick",
+            Binding::TickRate => "ticks per second",
+            Binding::Eccentricity => "eccentricity",
+            Binding::Fragments => "fragments",
+            Binding::EscapeRadius => "escape radius",
+        }
+    }
+    pub fn description(&self) -> &'static str {
+        match self {
+            Binding::Color(_) => "One channel of the colour new bodies get",
+            Binding::Mass => "Mass of new bodies, which is how hard they pull",
+            Binding::Radius => "Size of new bodies, bigger bodies collide sooner",
+            Binding::Gravity => "The gravitational constant, how strong every pull is",
+            Binding::Dt => "Simulated time that passes in each step",
+            Binding::TrailLength => "How long trails last before fading, 0 hides them",
+            Binding::Zoom => "How far the camera is zoomed in",
+            Binding::Substeps => "Steps per tick, more is slower but more accurate",
+            Binding::TickRate => "Physics ticks per second of real time",
+            Binding::Eccentricity => "How stretched orbits of launched bodies are, 0 is a circle",
+            Binding::Fragments => "How many pieces a body breaks into when it is torn apart",
+            Binding::EscapeRadius => {
+                "How far from everything else a body has to get before it can escape"
+            }
+        }
+    }
+    //whether `value` makes sense for this binding at all
+    pub fn accepts(&self, value: f32) -> bool {
+        if !value.is_finite() {
+            return false;
+        }
+        match self {
+            Binding::Color(_) => (0.0..=255.0).contains(&value),
+            Binding::TrailLength => value >= 0.0,
+            Binding::Substeps => value >= 1.0,
+            Binding::Eccentricity => (0.0..=MAX_ECCENTRICITY).contains(&value),
+            Binding::Fragments => (MIN_FRAGMENTS as f32..=MAX_FRAGMENTS as f32).contains(&value),
+            _ => value > 0.0,
+        }
+    }
+    pub fn format(&self, value: f32) -> String {
+        match self {
+            Binding::Color(_)
+            | Binding::TrailLength
+            | Binding::Substeps
+            | Binding::TickRate
+            | Binding::Fragments
+            | Binding::EscapeRadius => {
+                format!("{}", value.round())
+            }
+            Binding::Dt => format!("{:.3}", value),
+            _ => format!("{:.2}", value),
+        }
+    }
+    pub fn get(&self, example: &ExamplePlanet, space: &WorldSpace) -> f32 {
+        match self {
+            Binding::Color(channel) => {
+                let color = example.shape.fill_color();
+                (match channel {
+                    ColorType::Red => color.r,
+                    ColorType::Green => color.g,
+                    ColorType::Blue => color.b,
+                    ColorType::Alpha => color.a,
+                }) as f32
+            }
+            Binding::Mass => example.mass(),
+            Binding::Radius => example.radius(),
+            Binding::Gravity => space.gravity(),
+            Binding::Dt => space.dt(),
+            Binding::TrailLength => space.trail_length() as f32,
+            Binding::Zoom => space.zoom(),
+            Binding::Substeps => space.substeps() as f32,
+            Binding::TickRate => space.tick_rate(),
+            Binding::Eccentricity => example.orbit().eccentricity,
+            Binding::Fragments => space.fragment_count() as f32,
+            Binding::EscapeRadius => space.escape_radius(),
+        }
+    }
+    pub fn set(&self, value: f32, example: &mut ExamplePlanet, space: &mut WorldSpace) {
+        match self {
+            Binding::Color(channel) => {
+                let value = value.round().clamp(0.0, 255.0) as u8;
+                let mut color = example.shape.fill_color();
+                match channel {
+                    ColorType::Red => color.r = value,
+                    ColorType::Green => color.g = value,
+                    ColorType::Blue => color.b = value,
+                    ColorType::Alpha => color.a = value,
+                }
+                example.shape.set_fill_color(color);
+            }
+            Binding::Mass => {
+                example.set_mass(value);
+            }
+            Binding::Radius => {
+                example.set_radius(value);
+            }
+            Binding::Gravity => space.set_gravity(value),
+            Binding::Dt => space.set_dt(value),
+            Binding::TrailLength => space.set_trail_length(value.round().max(0.0) as usize),
+            Binding::Zoom => space.set_zoom(value),
+            Binding::Substeps => space.set_substeps(value.round().max(1.0) as u32),
+            Binding::TickRate => space.set_tick_rate(value),
+            Binding::Eccentricity => {
+                example.orbit_mut().eccentricity = value.clamp(0.0, MAX_ECCENTRICITY)
+            }
+            Binding::Fragments => space.set_fragment_count(value.round() as u32),
+            Binding::EscapeRadius => space.set_escape_radius(value),
+        }
+    }
 }
 impl PartialOrd for dyn Widget {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {